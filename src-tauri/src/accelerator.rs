@@ -0,0 +1,464 @@
+use std::fmt;
+use std::str::FromStr;
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+/// `CommandOrControl`: Command on macOS, Control everywhere else.
+#[cfg(target_os = "macos")]
+const COMMAND_OR_CONTROL: Modifiers = Modifiers::SUPER;
+#[cfg(not(target_os = "macos"))]
+const COMMAND_OR_CONTROL: Modifiers = Modifiers::CONTROL;
+
+/// Modifier names used when formatting, in display order.
+#[cfg(target_os = "macos")]
+const MODIFIER_NAMES: &[(Modifiers, &str)] = &[
+    (Modifiers::CONTROL, "Control"),
+    (Modifiers::ALT, "Option"),
+    (Modifiers::SHIFT, "Shift"),
+    (Modifiers::SUPER, "Command"),
+];
+#[cfg(not(target_os = "macos"))]
+const MODIFIER_NAMES: &[(Modifiers, &str)] = &[
+    (Modifiers::CONTROL, "Control"),
+    (Modifiers::ALT, "Alt"),
+    (Modifiers::SHIFT, "Shift"),
+    (Modifiers::SUPER, "Super"),
+];
+
+/// Canonical key names. The first entry for a code is what `Display` emits.
+const KEY_NAMES: &[(&str, Code)] = &[
+    ("Space", Code::Space),
+    ("A", Code::KeyA),
+    ("B", Code::KeyB),
+    ("C", Code::KeyC),
+    ("D", Code::KeyD),
+    ("E", Code::KeyE),
+    ("F", Code::KeyF),
+    ("G", Code::KeyG),
+    ("H", Code::KeyH),
+    ("I", Code::KeyI),
+    ("J", Code::KeyJ),
+    ("K", Code::KeyK),
+    ("L", Code::KeyL),
+    ("M", Code::KeyM),
+    ("N", Code::KeyN),
+    ("O", Code::KeyO),
+    ("P", Code::KeyP),
+    ("Q", Code::KeyQ),
+    ("R", Code::KeyR),
+    ("S", Code::KeyS),
+    ("T", Code::KeyT),
+    ("U", Code::KeyU),
+    ("V", Code::KeyV),
+    ("W", Code::KeyW),
+    ("X", Code::KeyX),
+    ("Y", Code::KeyY),
+    ("Z", Code::KeyZ),
+    ("0", Code::Digit0),
+    ("1", Code::Digit1),
+    ("2", Code::Digit2),
+    ("3", Code::Digit3),
+    ("4", Code::Digit4),
+    ("5", Code::Digit5),
+    ("6", Code::Digit6),
+    ("7", Code::Digit7),
+    ("8", Code::Digit8),
+    ("9", Code::Digit9),
+    ("F1", Code::F1),
+    ("F2", Code::F2),
+    ("F3", Code::F3),
+    ("F4", Code::F4),
+    ("F5", Code::F5),
+    ("F6", Code::F6),
+    ("F7", Code::F7),
+    ("F8", Code::F8),
+    ("F9", Code::F9),
+    ("F10", Code::F10),
+    ("F11", Code::F11),
+    ("F12", Code::F12),
+    ("F13", Code::F13),
+    ("F14", Code::F14),
+    ("F15", Code::F15),
+    ("F16", Code::F16),
+    ("F17", Code::F17),
+    ("F18", Code::F18),
+    ("F19", Code::F19),
+    ("F20", Code::F20),
+    ("F21", Code::F21),
+    ("F22", Code::F22),
+    ("F23", Code::F23),
+    ("F24", Code::F24),
+    ("Home", Code::Home),
+    ("End", Code::End),
+    ("PageUp", Code::PageUp),
+    ("PageDown", Code::PageDown),
+    ("Up", Code::ArrowUp),
+    ("Down", Code::ArrowDown),
+    ("Left", Code::ArrowLeft),
+    ("Right", Code::ArrowRight),
+    ("Enter", Code::Enter),
+    ("Escape", Code::Escape),
+    ("Tab", Code::Tab),
+    ("Backspace", Code::Backspace),
+    ("Delete", Code::Delete),
+    ("Insert", Code::Insert),
+    ("PrintScreen", Code::PrintScreen),
+    ("ScrollLock", Code::ScrollLock),
+    ("Pause", Code::Pause),
+    ("CapsLock", Code::CapsLock),
+    ("NumLock", Code::NumLock),
+    ("`", Code::Backquote),
+    ("-", Code::Minus),
+    ("=", Code::Equal),
+    ("[", Code::BracketLeft),
+    ("]", Code::BracketRight),
+    ("\\", Code::Backslash),
+    (";", Code::Semicolon),
+    ("'", Code::Quote),
+    (",", Code::Comma),
+    (".", Code::Period),
+    ("/", Code::Slash),
+    ("Num0", Code::Numpad0),
+    ("Num1", Code::Numpad1),
+    ("Num2", Code::Numpad2),
+    ("Num3", Code::Numpad3),
+    ("Num4", Code::Numpad4),
+    ("Num5", Code::Numpad5),
+    ("Num6", Code::Numpad6),
+    ("Num7", Code::Numpad7),
+    ("Num8", Code::Numpad8),
+    ("Num9", Code::Numpad9),
+    ("NumAdd", Code::NumpadAdd),
+    ("NumSubtract", Code::NumpadSubtract),
+    ("NumMultiply", Code::NumpadMultiply),
+    ("NumDivide", Code::NumpadDivide),
+    ("NumDecimal", Code::NumpadDecimal),
+    ("NumEnter", Code::NumpadEnter),
+    ("NumEqual", Code::NumpadEqual),
+    ("MediaPlayPause", Code::MediaPlayPause),
+    ("MediaStop", Code::MediaStop),
+    ("MediaNextTrack", Code::MediaTrackNext),
+    ("MediaPrevTrack", Code::MediaTrackPrevious),
+    ("VolumeUp", Code::AudioVolumeUp),
+    ("VolumeDown", Code::AudioVolumeDown),
+    ("VolumeMute", Code::AudioVolumeMute),
+];
+
+/// Extra spellings accepted when parsing, including the browser
+/// `KeyboardEvent.key`/`code` names the settings window may send.
+const KEY_ALIASES: &[(&str, Code)] = &[
+    ("ArrowUp", Code::ArrowUp),
+    ("ArrowDown", Code::ArrowDown),
+    ("ArrowLeft", Code::ArrowLeft),
+    ("ArrowRight", Code::ArrowRight),
+    ("Return", Code::Enter),
+    ("Esc", Code::Escape),
+    ("Del", Code::Delete),
+    ("Ins", Code::Insert),
+    ("Backquote", Code::Backquote),
+    ("Minus", Code::Minus),
+    ("Equal", Code::Equal),
+    ("BracketLeft", Code::BracketLeft),
+    ("BracketRight", Code::BracketRight),
+    ("Backslash", Code::Backslash),
+    ("Semicolon", Code::Semicolon),
+    ("Quote", Code::Quote),
+    ("Comma", Code::Comma),
+    ("Period", Code::Period),
+    ("Slash", Code::Slash),
+    ("NumpadAdd", Code::NumpadAdd),
+    ("NumpadSubtract", Code::NumpadSubtract),
+    ("NumpadMultiply", Code::NumpadMultiply),
+    ("NumpadDivide", Code::NumpadDivide),
+    ("NumpadDecimal", Code::NumpadDecimal),
+    ("NumpadEnter", Code::NumpadEnter),
+    ("NumpadEqual", Code::NumpadEqual),
+    ("MediaTrackNext", Code::MediaTrackNext),
+    ("MediaTrackPrevious", Code::MediaTrackPrevious),
+    ("AudioVolumeUp", Code::AudioVolumeUp),
+    ("AudioVolumeDown", Code::AudioVolumeDown),
+    ("AudioVolumeMute", Code::AudioVolumeMute),
+];
+
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+    match name.to_uppercase().as_str() {
+        "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCONTROL" | "CMDORCTRL" => {
+            Some(COMMAND_OR_CONTROL)
+        }
+        "CONTROL" | "CTRL" => Some(Modifiers::CONTROL),
+        "SUPER" | "META" | "COMMAND" | "CMD" | "WIN" | "WINDOWS" => Some(Modifiers::SUPER),
+        "SHIFT" => Some(Modifiers::SHIFT),
+        "ALT" | "OPTION" => Some(Modifiers::ALT),
+        _ => None,
+    }
+}
+
+fn parse_key(name: &str) -> Option<Code> {
+    // `Numpad0`..`Numpad9` and `Digit0`..`Digit9`/`KeyA`..`KeyZ` from
+    // `KeyboardEvent.code`
+    let name = name
+        .strip_prefix("Numpad")
+        .filter(|rest| rest.len() == 1 && rest.chars().all(|c| c.is_ascii_digit()))
+        .map(|digit| format!("Num{}", digit))
+        .or_else(|| {
+            ["Digit", "Key"].iter().find_map(|prefix| {
+                name.strip_prefix(prefix)
+                    .filter(|rest| rest.len() == 1)
+                    .map(str::to_string)
+            })
+        })
+        .unwrap_or_else(|| name.to_string());
+
+    KEY_NAMES
+        .iter()
+        .chain(KEY_ALIASES)
+        .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+        .map(|(_, code)| *code)
+}
+
+fn key_name(code: Code) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key)| *key == code)
+        .map(|(name, _)| *name)
+}
+
+/// A shortcut that formats back to a string `parse_shortcut` accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Accelerator(pub Shortcut);
+
+impl FromStr for Accelerator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::empty();
+        let mut code: Option<Code> = None;
+
+        for part in s.split('+') {
+            let part = part.trim();
+            if part.is_empty() {
+                return Err(format!("Empty key in shortcut: {}", s));
+            }
+
+            if let Some(modifier) = parse_modifier(part) {
+                modifiers |= modifier;
+                continue;
+            }
+
+            let key = parse_key(part).ok_or_else(|| format!("Unknown key: {}", part))?;
+            if code.replace(key).is_some() {
+                return Err(format!("More than one key in shortcut: {}", s));
+            }
+        }
+
+        let code = code.ok_or("No key specified")?;
+        Ok(Accelerator(Shortcut::new(Some(modifiers), code)))
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shortcut = &self.0;
+        for (modifier, name) in MODIFIER_NAMES {
+            if shortcut.mods.contains(*modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match key_name(shortcut.key) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{:?}", shortcut.key),
+        }
+    }
+}
+
+pub fn parse_shortcut(s: &str) -> Result<Shortcut, String> {
+    s.parse::<Accelerator>().map(|accelerator| accelerator.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Shortcut {
+        parse_shortcut(s).unwrap_or_else(|e| panic!("{}: {}", s, e))
+    }
+
+    fn error(s: &str) -> String {
+        parse_shortcut(s).expect_err(s)
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn command_or_control_is_command_on_macos() {
+        for name in [
+            "CommandOrControl",
+            "CommandOrCtrl",
+            "CmdOrControl",
+            "CmdOrCtrl",
+        ] {
+            assert_eq!(parse(&format!("{}+A", name)).mods, Modifiers::SUPER);
+        }
+        assert_eq!(
+            Accelerator(parse("CommandOrControl+Shift+Space")).to_string(),
+            "Shift+Command+Space"
+        );
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn command_or_control_is_control_elsewhere() {
+        for name in [
+            "CommandOrControl",
+            "CommandOrCtrl",
+            "CmdOrControl",
+            "CmdOrCtrl",
+        ] {
+            assert_eq!(parse(&format!("{}+A", name)).mods, Modifiers::CONTROL);
+        }
+        assert_eq!(
+            Accelerator(parse("CommandOrControl+Shift+Space")).to_string(),
+            "Control+Shift+Space"
+        );
+    }
+
+    #[test]
+    fn control_and_super_are_distinct() {
+        for name in ["Control", "Ctrl", "ctrl", "CONTROL"] {
+            assert_eq!(parse(&format!("{}+A", name)).mods, Modifiers::CONTROL);
+        }
+        for name in ["Super", "Meta", "Command", "Cmd", "Win", "Windows"] {
+            assert_eq!(parse(&format!("{}+A", name)).mods, Modifiers::SUPER);
+        }
+        assert_eq!(
+            parse("Ctrl+Super+A").mods,
+            Modifiers::CONTROL | Modifiers::SUPER
+        );
+        assert_eq!(parse("Option+A").mods, Modifiers::ALT);
+        assert_eq!(parse("Alt+Shift+A").mods, Modifiers::ALT | Modifiers::SHIFT);
+    }
+
+    #[test]
+    fn function_keys() {
+        let codes = [
+            Code::F1,
+            Code::F2,
+            Code::F3,
+            Code::F4,
+            Code::F5,
+            Code::F6,
+            Code::F7,
+            Code::F8,
+            Code::F9,
+            Code::F10,
+            Code::F11,
+            Code::F12,
+            Code::F13,
+            Code::F14,
+            Code::F15,
+            Code::F16,
+            Code::F17,
+            Code::F18,
+            Code::F19,
+            Code::F20,
+            Code::F21,
+            Code::F22,
+            Code::F23,
+            Code::F24,
+        ];
+        for (n, code) in (1..=24).zip(codes) {
+            let shortcut = parse(&format!("F{}", n));
+            assert_eq!(shortcut.key, code);
+            assert_eq!(Accelerator(shortcut).to_string(), format!("F{}", n));
+            assert_eq!(parse(&format!("f{}", n)).key, code);
+        }
+    }
+
+    #[test]
+    fn punctuation_keys() {
+        let keys = [
+            ("`", Code::Backquote),
+            ("-", Code::Minus),
+            ("=", Code::Equal),
+            ("[", Code::BracketLeft),
+            ("]", Code::BracketRight),
+            ("\\", Code::Backslash),
+            (";", Code::Semicolon),
+            ("'", Code::Quote),
+            (",", Code::Comma),
+            (".", Code::Period),
+            ("/", Code::Slash),
+        ];
+        for (name, code) in keys {
+            assert_eq!(parse(&format!("Control+{}", name)).key, code);
+        }
+    }
+
+    #[test]
+    fn numpad_keys() {
+        assert_eq!(parse("Num0").key, Code::Numpad0);
+        assert_eq!(parse("Num9").key, Code::Numpad9);
+        assert_eq!(parse("NumAdd").key, Code::NumpadAdd);
+        assert_eq!(parse("NumSubtract").key, Code::NumpadSubtract);
+        assert_eq!(parse("NumMultiply").key, Code::NumpadMultiply);
+        assert_eq!(parse("NumDivide").key, Code::NumpadDivide);
+        assert_eq!(parse("NumDecimal").key, Code::NumpadDecimal);
+        assert_eq!(parse("NumEnter").key, Code::NumpadEnter);
+        assert_eq!(parse("NumEqual").key, Code::NumpadEqual);
+    }
+
+    #[test]
+    fn media_keys() {
+        assert_eq!(parse("MediaPlayPause").key, Code::MediaPlayPause);
+        assert_eq!(parse("MediaStop").key, Code::MediaStop);
+        assert_eq!(parse("MediaNextTrack").key, Code::MediaTrackNext);
+        assert_eq!(parse("MediaPrevTrack").key, Code::MediaTrackPrevious);
+        assert_eq!(parse("VolumeUp").key, Code::AudioVolumeUp);
+        assert_eq!(parse("VolumeDown").key, Code::AudioVolumeDown);
+        assert_eq!(parse("VolumeMute").key, Code::AudioVolumeMute);
+    }
+
+    #[test]
+    fn keyboard_event_code_aliases() {
+        assert_eq!(parse("Shift+KeyA").key, Code::KeyA);
+        assert_eq!(parse("Shift+KeyZ").key, Code::KeyZ);
+        assert_eq!(parse("Alt+Digit0").key, Code::Digit0);
+        assert_eq!(parse("Alt+Digit9").key, Code::Digit9);
+        assert_eq!(parse("Control+Numpad7").key, Code::Numpad7);
+        assert_eq!(parse("Space").key, Code::Space);
+        for (name, code) in KEY_ALIASES {
+            assert_eq!(parse(name).key, *code, "{}", name);
+        }
+        assert_eq!(parse("Return").key, Code::Enter);
+        assert_eq!(parse("Esc").key, Code::Escape);
+    }
+
+    #[test]
+    fn rejects_malformed_shortcuts() {
+        assert_eq!(error(""), "Empty key in shortcut: ");
+        assert_eq!(error("Control+"), "Empty key in shortcut: Control+");
+        assert_eq!(error("Control++A"), "Empty key in shortcut: Control++A");
+        assert_eq!(error("Control+ +A"), "Empty key in shortcut: Control+ +A");
+        assert_eq!(error("A+B"), "More than one key in shortcut: A+B");
+        assert_eq!(error("Control+Shift"), "No key specified");
+        assert_eq!(error("Hyper+A"), "Unknown key: Hyper");
+        assert_eq!(error("Control+KeyAB"), "Unknown key: KeyAB");
+        assert_eq!(error("Numpad10"), "Unknown key: Numpad10");
+    }
+
+    #[test]
+    fn display_round_trips_every_key() {
+        let all = Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT | Modifiers::SUPER;
+        for (name, code) in KEY_NAMES {
+            for mods in [Modifiers::empty(), Modifiers::SHIFT, all] {
+                let accelerator = Accelerator(Shortcut::new(Some(mods), *code));
+                let formatted = accelerator.to_string();
+                assert_eq!(
+                    formatted.parse::<Accelerator>(),
+                    Ok(accelerator),
+                    "{}",
+                    formatted
+                );
+                assert!(formatted.ends_with(name), "{}", formatted);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Whether to keep recordings, and how much disk they may use.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSettings {
+    pub enabled: bool,
+    /// Oldest recordings are deleted beyond this; `None` means no limit.
+    pub max_megabytes: Option<u64>,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_megabytes: Some(500),
+        }
+    }
+}
+
+/// Recordings in `<app data dir>/recordings/<history id>.wav`.
+pub struct AudioArchive {
+    dir: PathBuf,
+    settings: Mutex<ArchiveSettings>,
+}
+
+impl AudioArchive {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            settings: Mutex::new(ArchiveSettings::default()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.settings.lock().unwrap().enabled
+    }
+
+    pub fn set_settings(&self, settings: ArchiveSettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{}.wav", id))
+    }
+
+    pub fn save(&self, id: u64, wav: &[u8]) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        let path = self.path(id);
+        fs::write(&path, wav).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn load(&self, id: u64) -> Result<Vec<u8>, String> {
+        let path = self.path(id);
+        fs::read(&path).map_err(|e| format!("No recording for entry {}: {}", id, e))
+    }
+
+    pub fn delete(&self, id: u64) {
+        let path = self.path(id);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("Failed to delete {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// IDs with a recording on disk, with their size in bytes, oldest first.
+    fn recordings(&self) -> Vec<(u64, u64)> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut recordings: Vec<(u64, u64)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != "wav" {
+                    return None;
+                }
+                let id = path.file_stem()?.to_str()?.parse().ok()?;
+                Some((id, entry.metadata().ok()?.len()))
+            })
+            .collect();
+        recordings.sort_by_key(|(id, _)| *id);
+        recordings
+    }
+
+    pub fn archived_ids(&self) -> Vec<u64> {
+        self.recordings().into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Delete recordings whose history entry is gone, then the oldest ones
+    /// until the archive fits the quota.
+    pub fn prune(&self, live_ids: &[u64]) {
+        let max_bytes = self
+            .settings
+            .lock()
+            .unwrap()
+            .max_megabytes
+            .map(|mb| mb * 1024 * 1024);
+
+        let mut kept = Vec::new();
+        for (id, size) in self.recordings() {
+            if live_ids.contains(&id) {
+                kept.push((id, size));
+            } else {
+                self.delete(id);
+            }
+        }
+
+        let Some(max_bytes) = max_bytes else {
+            return;
+        };
+        let mut total: u64 = kept.iter().map(|(_, size)| size).sum();
+        for (id, size) in kept {
+            if total <= max_bytes {
+                break;
+            }
+            println!("Archive over quota - deleting recording {}", id);
+            self.delete(id);
+            total -= size;
+        }
+    }
+}
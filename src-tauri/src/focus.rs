@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::normalize::NormalizeSettings;
+
+/// Per-application overrides, matched against the app that had focus when
+/// recording started.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppContextRule {
+    /// Bundle identifier on macOS (`com.apple.Terminal`), window class on
+    /// Linux (`gnome-terminal-server`). Compared case-insensitively.
+    pub app: String,
+    /// Profile to use when dictating with the default shortcut.
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Replaces the global text style for this app.
+    #[serde(default)]
+    pub normalize: Option<NormalizeSettings>,
+}
+
+pub fn find_app_rule<'a>(rules: &'a [AppContextRule], app: &str) -> Option<&'a AppContextRule> {
+    rules.iter().find(|rule| rule.app.eq_ignore_ascii_case(app))
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Identifier of the frontmost application, if it can be determined.
+#[cfg(target_os = "macos")]
+pub fn frontmost_app() -> Option<String> {
+    run(
+        "osascript",
+        &[
+            "-e",
+            r#"tell application "System Events" to get bundle identifier of first application process whose frontmost is true"#,
+        ],
+    )
+}
+
+/// Window class of the active window. Uses X11 (`xprop`), which on Wayland
+/// only sees XWayland windows, so Sway and Hyprland are asked directly.
+#[cfg(target_os = "linux")]
+pub fn frontmost_app() -> Option<String> {
+    if std::env::var_os("SWAYSOCK").is_some() {
+        let tree = run("swaymsg", &["-t", "get_tree"])?;
+        return sway_focused_app(&serde_json::from_str(&tree).ok()?);
+    }
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let window: serde_json::Value =
+            serde_json::from_str(&run("hyprctl", &["activewindow", "-j"])?).ok()?;
+        return window["class"].as_str().map(str::to_string);
+    }
+
+    // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+    let active = run("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window_id = active.split_whitespace().last()?;
+    // WM_CLASS(STRING) = "gnome-terminal-server", "Gnome-terminal"
+    let class = run("xprop", &["-id", window_id, "WM_CLASS"])?;
+    class
+        .split('"')
+        .nth(1)
+        .filter(|class| !class.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "linux")]
+fn sway_focused_app(node: &serde_json::Value) -> Option<String> {
+    if node["focused"].as_bool() == Some(true) {
+        return node["app_id"]
+            .as_str()
+            .or_else(|| node["window_properties"]["class"].as_str())
+            .map(str::to_string);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[*key].as_array())
+        .flatten()
+        .find_map(sway_focused_app)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn frontmost_app() -> Option<String> {
+    None
+}
@@ -0,0 +1,703 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Gemini API types
+#[derive(Serialize)]
+struct GeminiRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<Content>,
+    contents: Vec<Content>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Serialize)]
+struct Content {
+    parts: Vec<Part>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Part {
+    Text { text: String },
+    InlineData { inline_data: InlineData },
+}
+
+#[derive(Serialize)]
+struct InlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Serialize)]
+struct GenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<ThinkingConfig>,
+}
+
+#[derive(Serialize)]
+struct ThinkingConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_budget: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_level: Option<String>,
+}
+
+/// User-facing `generationConfig` settings.
+///
+/// `None` leaves the field to the model's default. A thinking budget of `0`
+/// means "as little thinking as the model allows" and `-1` means dynamic.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerationSettings {
+    pub temperature: Option<f32>,
+    pub max_output_tokens: Option<u32>,
+    pub thinking_budget: Option<i32>,
+    pub response_mime_type: Option<String>,
+}
+
+impl Default for GenerationSettings {
+    /// Deterministic, low-latency output suited to dictation.
+    fn default() -> Self {
+        Self {
+            temperature: Some(0.0),
+            max_output_tokens: None,
+            thinking_budget: Some(0),
+            response_mime_type: Some("text/plain".to_string()),
+        }
+    }
+}
+
+impl GenerationSettings {
+    fn to_config(&self, model: &str) -> GenerationConfig {
+        GenerationConfig {
+            temperature: self.temperature,
+            max_output_tokens: self.max_output_tokens,
+            response_mime_type: self
+                .response_mime_type
+                .clone()
+                .filter(|mime| !mime.is_empty()),
+            thinking_config: self
+                .thinking_budget
+                .and_then(|budget| thinking_config(model, budget)),
+        }
+    }
+}
+
+/// Map a thinking budget onto what `model` accepts. `-1` (dynamic) leaves
+/// the model default; Gemini 3 expresses minimal thinking as
+/// `thinkingLevel: low`, and 2.5 Pro cannot go below 128 tokens.
+fn thinking_config(model: &str, budget: i32) -> Option<ThinkingConfig> {
+    if budget < 0 {
+        return None;
+    }
+
+    if budget == 0 && model.starts_with("gemini-3") {
+        return Some(ThinkingConfig {
+            thinking_budget: None,
+            thinking_level: Some("low".to_string()),
+        });
+    }
+
+    let budget = if budget == 0 && model.contains("2.5-pro") {
+        128
+    } else {
+        budget
+    };
+    Some(ThinkingConfig {
+        thinking_budget: Some(budget),
+        thinking_level: None,
+    })
+}
+
+/// Languages for translation profiles. Names are passed to the model as
+/// written, so "Japanese" and "日本語" both work.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationSettings {
+    pub source_language: String,
+    pub target_language: String,
+    /// Output the transcript in the source language above the translation.
+    pub include_original: bool,
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            source_language: "Japanese".to_string(),
+            target_language: "English".to_string(),
+            include_original: false,
+        }
+    }
+}
+
+impl TranslationSettings {
+    /// Instruction sent next to the audio, after the profile prompt.
+    fn instruction(&self) -> String {
+        let mut instruction = format!(
+            "音声は{}です。{}に翻訳してください。",
+            self.source_language, self.target_language
+        );
+        if self.include_original {
+            instruction.push_str(&format!(
+                "まず{}の書き起こしを出力し、空行を1行挟んでから{}の翻訳を出力してください。",
+                self.source_language, self.target_language
+            ));
+        } else {
+            instruction.push_str("翻訳結果のみを出力してください。");
+        }
+        instruction
+    }
+}
+
+/// Everything needed to address the model for one transcription.
+#[derive(Clone)]
+pub struct GeminiConfig {
+    pub api_key: String,
+    pub model: String,
+    pub generation: GenerationSettings,
+    pub prompt: String,
+    /// Send the prompt as `systemInstruction` instead of a user part after
+    /// the audio.
+    pub use_system_instruction: bool,
+    /// Text selected in the focused app, sent after the audio so the
+    /// spoken instruction can rewrite it.
+    pub selection: Option<String>,
+    /// Set for translation profiles; adds the language instruction.
+    pub translation: Option<TranslationSettings>,
+    /// Earlier transcripts from the same app, oldest first, sent before the
+    /// audio so wording and punctuation carry over.
+    pub context: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    candidates: Option<Vec<Candidate>>,
+    prompt_feedback: Option<PromptFeedback>,
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    content: Option<CandidateContent>,
+    finish_reason: Option<String>,
+    safety_ratings: Option<Vec<SafetyRating>>,
+}
+
+#[derive(Deserialize)]
+struct CandidateContent {
+    parts: Option<Vec<ResponsePart>>,
+}
+
+#[derive(Deserialize)]
+struct ResponsePart {
+    text: Option<String>,
+    /// Set on thought summaries returned by thinking models.
+    thought: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFeedback {
+    block_reason: Option<String>,
+    safety_ratings: Option<Vec<SafetyRating>>,
+}
+
+#[derive(Deserialize)]
+struct SafetyRating {
+    category: String,
+    probability: Option<String>,
+    blocked: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    prompt_token_count: Option<u32>,
+    candidates_token_count: Option<u32>,
+    thoughts_token_count: Option<u32>,
+    total_token_count: Option<u32>,
+}
+
+/// Ways a transcription request can fail.
+#[derive(Debug)]
+pub enum GeminiError {
+    Http(String),
+    Api(String),
+    Parse(String),
+    /// The prompt or the candidate was blocked (`promptFeedback.blockReason`
+    /// or a safety-related `finishReason`).
+    Blocked {
+        reason: String,
+        categories: Vec<String>,
+    },
+    /// The model finished normally but produced no text.
+    Empty { finish_reason: Option<String> },
+    /// Output hit `maxOutputTokens`; the partial text is kept for logging.
+    Truncated { partial: String },
+}
+
+impl fmt::Display for GeminiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeminiError::Http(e) => write!(f, "HTTP request failed: {}", e),
+            GeminiError::Api(e) => write!(f, "API error: {}", e),
+            GeminiError::Parse(e) => write!(f, "JSON parse error: {}", e),
+            GeminiError::Blocked { reason, categories } if categories.is_empty() => {
+                write!(f, "Blocked by Gemini ({})", reason)
+            }
+            GeminiError::Blocked { reason, categories } => {
+                write!(f, "Blocked by Gemini ({}: {})", reason, categories.join(", "))
+            }
+            GeminiError::Empty {
+                finish_reason: Some(reason),
+            } => write!(f, "Empty transcription (finish reason: {})", reason),
+            GeminiError::Empty {
+                finish_reason: None,
+            } => write!(f, "Empty transcription"),
+            GeminiError::Truncated { partial } => write!(
+                f,
+                "Transcription truncated at the output token limit ({} chars received)",
+                partial.chars().count()
+            ),
+        }
+    }
+}
+
+/// `finishReason` values that mean the candidate was withheld.
+const BLOCKING_FINISH_REASONS: &[&str] = &[
+    "SAFETY",
+    "RECITATION",
+    "LANGUAGE",
+    "BLOCKLIST",
+    "PROHIBITED_CONTENT",
+    "SPII",
+];
+
+fn blocked_categories(ratings: Option<&Vec<SafetyRating>>) -> Vec<String> {
+    ratings
+        .into_iter()
+        .flatten()
+        .filter(|r| r.blocked.unwrap_or(false))
+        .map(|r| match &r.probability {
+            Some(p) => format!("{} {}", r.category, p),
+            None => r.category.clone(),
+        })
+        .collect()
+}
+
+impl Candidate {
+    /// All non-thought text parts joined in order.
+    fn text(&self) -> String {
+        self.content
+            .iter()
+            .flat_map(|c| c.parts.iter().flatten())
+            .filter(|p| !p.thought.unwrap_or(false))
+            .filter_map(|p| p.text.as_deref())
+            .collect()
+    }
+
+    fn blocked(&self) -> Option<GeminiError> {
+        let reason = self.finish_reason.as_deref()?;
+        if !BLOCKING_FINISH_REASONS.contains(&reason) {
+            return None;
+        }
+        Some(GeminiError::Blocked {
+            reason: reason.to_string(),
+            categories: blocked_categories(self.safety_ratings.as_ref()),
+        })
+    }
+
+    fn truncated(&self) -> bool {
+        self.finish_reason.as_deref() == Some("MAX_TOKENS")
+    }
+}
+
+fn extract_text(response: GeminiResponse) -> Result<String, GeminiError> {
+    if let Some(usage) = &response.usage_metadata {
+        println!(
+            "Token usage: prompt={:?} output={:?} thoughts={:?} total={:?}",
+            usage.prompt_token_count,
+            usage.candidates_token_count,
+            usage.thoughts_token_count,
+            usage.total_token_count
+        );
+    }
+
+    if let Some(feedback) = &response.prompt_feedback {
+        if let Some(reason) = &feedback.block_reason {
+            return Err(GeminiError::Blocked {
+                reason: reason.clone(),
+                categories: blocked_categories(feedback.safety_ratings.as_ref()),
+            });
+        }
+    }
+
+    let candidates = response.candidates.unwrap_or_default();
+
+    // Candidates are alternatives, not continuations: use the first usable one
+    for candidate in &candidates {
+        if candidate.blocked().is_some() {
+            continue;
+        }
+        let text = candidate.text().trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        if candidate.truncated() {
+            return Err(GeminiError::Truncated { partial: text });
+        }
+        return Ok(text);
+    }
+
+    // Nothing usable; report why the first candidate failed
+    match candidates.first() {
+        Some(candidate) => Err(candidate.blocked().unwrap_or(GeminiError::Empty {
+            finish_reason: candidate.finish_reason.clone(),
+        })),
+        None => Err(GeminiError::Empty { finish_reason: None }),
+    }
+}
+
+const API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+fn build_request(config: &GeminiConfig, audio_data: &[u8]) -> GeminiRequest {
+    let base64_audio = base64::engine::general_purpose::STANDARD.encode(audio_data);
+    let prompt = Part::Text {
+        text: config.prompt.clone(),
+    };
+
+    let mut parts = Vec::new();
+
+    if !config.context.is_empty() {
+        parts.push(Part::Text {
+            text: format!(
+                "直前に同じアプリで入力した文字起こしです。用語・表記・句読点をこれに揃えてください。これ自体は出力しないでください。\n---\n{}",
+                config.context.join("\n---\n")
+            ),
+        });
+    }
+
+    parts.push(Part::InlineData {
+        inline_data: InlineData {
+            mime_type: "audio/wav".to_string(),
+            data: base64_audio,
+        },
+    });
+
+    if let Some(selection) = &config.selection {
+        parts.push(Part::Text {
+            text: format!("書き換え対象のテキスト:\n{}", selection),
+        });
+    }
+
+    if let Some(translation) = &config.translation {
+        parts.push(Part::Text {
+            text: translation.instruction(),
+        });
+    }
+
+    let system_instruction = if config.use_system_instruction {
+        Some(Content {
+            parts: vec![prompt],
+        })
+    } else {
+        parts.push(prompt);
+        None
+    };
+
+    GeminiRequest {
+        system_instruction,
+        contents: vec![Content { parts }],
+        generation_config: config.generation.to_config(&config.model),
+    }
+}
+
+async fn send(url: &str, request: &GeminiRequest) -> Result<reqwest::Response, GeminiError> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(url)
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| GeminiError::Http(e.to_string()))?;
+
+    if !response.status().is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(GeminiError::Api(error_text));
+    }
+
+    Ok(response)
+}
+
+pub async fn transcribe_with_gemini(
+    config: &GeminiConfig,
+    audio_data: &[u8],
+) -> Result<String, GeminiError> {
+    let request = build_request(config, audio_data);
+    let url = format!(
+        "{}/{}:generateContent?key={}",
+        API_BASE, config.model, config.api_key
+    );
+
+    let gemini_response: GeminiResponse = send(&url, &request)
+        .await?
+        .json()
+        .await
+        .map_err(|e| GeminiError::Parse(e.to_string()))?;
+
+    extract_text(gemini_response)
+}
+
+/// Merges `streamGenerateContent` chunks into a single response so the
+/// final result goes through the same checks as the non-streaming path.
+#[derive(Default)]
+struct StreamAccumulator {
+    text: String,
+    finish_reason: Option<String>,
+    safety_ratings: Option<Vec<SafetyRating>>,
+    prompt_feedback: Option<PromptFeedback>,
+    usage_metadata: Option<UsageMetadata>,
+}
+
+impl StreamAccumulator {
+    /// Fold in one chunk and return the text it added.
+    fn push(&mut self, chunk: GeminiResponse) -> String {
+        if chunk.prompt_feedback.is_some() {
+            self.prompt_feedback = chunk.prompt_feedback;
+        }
+        if chunk.usage_metadata.is_some() {
+            self.usage_metadata = chunk.usage_metadata;
+        }
+
+        let candidate = match chunk.candidates.and_then(|c| c.into_iter().next()) {
+            Some(candidate) => candidate,
+            None => return String::new(),
+        };
+
+        let delta = candidate.text();
+        self.text.push_str(&delta);
+        if candidate.finish_reason.is_some() {
+            self.finish_reason = candidate.finish_reason;
+        }
+        if candidate.safety_ratings.is_some() {
+            self.safety_ratings = candidate.safety_ratings;
+        }
+        delta
+    }
+
+    fn finish(self) -> Result<String, GeminiError> {
+        extract_text(GeminiResponse {
+            candidates: Some(vec![Candidate {
+                content: Some(CandidateContent {
+                    parts: Some(vec![ResponsePart {
+                        text: Some(self.text),
+                        thought: None,
+                    }]),
+                }),
+                finish_reason: self.finish_reason,
+                safety_ratings: self.safety_ratings,
+            }]),
+            prompt_feedback: self.prompt_feedback,
+            usage_metadata: self.usage_metadata,
+        })
+    }
+}
+
+/// Remove the complete server-sent events from `buffer` and return their
+/// `data:` payloads; a trailing partial event stays in the buffer.
+fn drain_events(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut events = Vec::new();
+    while let Some(pos) = buffer.windows(2).position(|w| w == b"\n\n") {
+        let event: Vec<u8> = buffer.drain(..pos + 2).collect();
+        let event = String::from_utf8_lossy(&event);
+
+        let data = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| data.trim_start())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !data.is_empty() {
+            events.push(data);
+        }
+    }
+    events
+}
+
+/// Like [`transcribe_with_gemini`], but reads the response as server-sent
+/// events and calls `on_text` with each new piece of text as it arrives.
+///
+/// Leading whitespace is held back so the first piece starts with visible
+/// text; the returned string is the full, trimmed transcript.
+pub async fn transcribe_with_gemini_stream<F>(
+    config: &GeminiConfig,
+    audio_data: &[u8],
+    mut on_text: F,
+) -> Result<String, GeminiError>
+where
+    F: FnMut(&str),
+{
+    let request = build_request(config, audio_data);
+    let url = format!(
+        "{}/{}:streamGenerateContent?alt=sse&key={}",
+        API_BASE, config.model, config.api_key
+    );
+
+    let mut response = send(&url, &request).await?;
+    let mut accumulator = StreamAccumulator::default();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut started = false;
+
+    loop {
+        let bytes = response
+            .chunk()
+            .await
+            .map_err(|e| GeminiError::Http(e.to_string()))?;
+        let end_of_stream = bytes.is_none();
+        match bytes {
+            // '\r' never occurs inside a multi-byte UTF-8 sequence, so it is
+            // safe to drop before splitting events on blank lines
+            Some(bytes) => buffer.extend(bytes.iter().filter(|&&b| b != b'\r')),
+            None => buffer.extend_from_slice(b"\n\n"),
+        }
+
+        for data in drain_events(&mut buffer) {
+            let chunk: GeminiResponse =
+                serde_json::from_str(&data).map_err(|e| GeminiError::Parse(e.to_string()))?;
+            let delta = accumulator.push(chunk);
+
+            let delta = if started { delta.as_str() } else { delta.trim_start() };
+            if !delta.is_empty() {
+                started = true;
+                on_text(delta);
+            }
+        }
+
+        if end_of_stream {
+            break;
+        }
+    }
+
+    accumulator.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!("../tests/fixtures/gemini/", $name))
+        };
+    }
+
+    fn response(json: &str) -> GeminiResponse {
+        serde_json::from_str(json).expect("valid fixture")
+    }
+
+    fn extract(json: &str) -> Result<String, GeminiError> {
+        extract_text(response(json))
+    }
+
+    #[test]
+    fn joins_multiple_parts() {
+        let text = extract(fixture!("multi_part.json")).unwrap();
+        assert_eq!(text, "Reactでコンポーネントを書きます。");
+    }
+
+    #[test]
+    fn skips_thought_parts() {
+        let text = extract(fixture!("thought_parts.json")).unwrap();
+        assert_eq!(text, "プルリクエストを作成してください。");
+    }
+
+    #[test]
+    fn falls_back_to_second_candidate() {
+        let text = extract(fixture!("second_candidate.json")).unwrap();
+        assert_eq!(text, "二番目の候補です。");
+    }
+
+    #[test]
+    fn reports_blocked_prompt() {
+        match extract(fixture!("prompt_blocked.json")) {
+            Err(GeminiError::Blocked { reason, categories }) => {
+                assert_eq!(reason, "PROHIBITED_CONTENT");
+                assert_eq!(categories, vec!["HARM_CATEGORY_HARASSMENT HIGH"]);
+            }
+            other => panic!("expected Blocked, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_safety_finish_reason() {
+        match extract(fixture!("safety.json")) {
+            Err(GeminiError::Blocked { reason, categories }) => {
+                assert_eq!(reason, "SAFETY");
+                assert_eq!(categories, vec!["HARM_CATEGORY_DANGEROUS_CONTENT MEDIUM"]);
+            }
+            other => panic!("expected Blocked, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_truncated_output() {
+        match extract(fixture!("max_tokens.json")) {
+            Err(GeminiError::Truncated { partial }) => {
+                assert_eq!(partial, "長い説明の途中で出力が")
+            }
+            other => panic!("expected Truncated, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_empty_output() {
+        match extract(fixture!("empty.json")) {
+            Err(GeminiError::Empty { finish_reason }) => {
+                assert_eq!(finish_reason.as_deref(), Some("STOP"))
+            }
+            other => panic!("expected Empty, got {:?}", other),
+        }
+        match extract("{}") {
+            Err(GeminiError::Empty { finish_reason }) => assert_eq!(finish_reason, None),
+            other => panic!("expected Empty, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn accumulates_sse_stream() {
+        let stream = fixture!("stream.sse").as_bytes();
+        let mut accumulator = StreamAccumulator::default();
+        let mut buffer = Vec::new();
+        let mut deltas = Vec::new();
+
+        // Small chunks split events and multi-byte characters, as the network does
+        for chunk in stream.chunks(7) {
+            buffer.extend(chunk.iter().filter(|&&b| b != b'\r'));
+            for data in drain_events(&mut buffer) {
+                deltas.push(accumulator.push(response(&data)));
+            }
+        }
+        buffer.extend_from_slice(b"\n\n");
+        for data in drain_events(&mut buffer) {
+            deltas.push(accumulator.push(response(&data)));
+        }
+
+        assert_eq!(deltas, vec!["\n今日は", "Tauriで", "アプリを作ります。"]);
+        assert_eq!(
+            accumulator.finish().unwrap(),
+            "今日はTauriでアプリを作ります。"
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::json_file;
+
+/// One finished transcription, stored as a line of `history.jsonl`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: u64,
+    /// Unix time in milliseconds when the transcript came back.
+    pub timestamp_ms: u64,
+    pub audio_duration_ms: u64,
+    /// Time from releasing the shortcut to having the transcript.
+    pub latency_ms: u64,
+    pub model: String,
+    pub profile_id: String,
+    /// App that had focus when recording started, if known.
+    #[serde(default)]
+    pub app: Option<String>,
+    pub text: String,
+}
+
+/// How much history to keep; `None` means no limit.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRetention {
+    pub max_entries: Option<usize>,
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self {
+            max_entries: Some(1000),
+            max_age_days: Some(30),
+        }
+    }
+}
+
+/// Which earlier transcripts are sent along with a recording so
+/// consecutive dictations stay consistent.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuitySettings {
+    pub enabled: bool,
+    pub max_transcripts: usize,
+    /// Only transcripts from the last this many seconds count.
+    pub window_secs: u64,
+}
+
+impl Default for ContinuitySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_transcripts: 3,
+            window_secs: 300,
+        }
+    }
+}
+
+/// Fields of a new entry; the store assigns the ID and timestamp.
+pub struct NewEntry {
+    pub audio_duration_ms: u64,
+    pub latency_ms: u64,
+    pub model: String,
+    pub profile_id: String,
+    pub app: Option<String>,
+    pub text: String,
+}
+
+/// Transcription history in `<app data dir>/history.jsonl`, oldest first,
+/// mirrored in memory.
+pub struct HistoryStore {
+    path: PathBuf,
+    entries: Mutex<Vec<HistoryEntry>>,
+    /// Highest ID ever assigned, so IDs of deleted entries (and their archived
+    /// recordings) are never handed out again.
+    last_id: Mutex<u64>,
+    retention: Mutex<HistoryRetention>,
+}
+
+/// Where the last assigned ID is kept, next to the history file.
+fn last_id_path(path: &Path) -> PathBuf {
+    path.with_extension("last-id")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(|line| match serde_json::from_str(line) {
+                    Ok(entry) => Some(entry),
+                    Err(e) => {
+                        eprintln!("Skipping unreadable history entry: {}", e);
+                        None
+                    }
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        let stored_id = fs::read_to_string(last_id_path(&path))
+            .ok()
+            .and_then(|content| content.trim().parse().ok())
+            .unwrap_or(0);
+        let last_id = entries
+            .iter()
+            .map(|entry: &HistoryEntry| entry.id)
+            .fold(stored_id, u64::max);
+
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            last_id: Mutex::new(last_id),
+            retention: Mutex::new(HistoryRetention::default()),
+        }
+    }
+
+    pub fn add(&self, new: NewEntry) -> Result<HistoryEntry, String> {
+        let mut entries = self.entries.lock().unwrap();
+        let id = {
+            let mut last_id = self.last_id.lock().unwrap();
+            *last_id += 1;
+            *last_id
+        };
+        let entry = HistoryEntry {
+            id,
+            timestamp_ms: now_ms(),
+            audio_duration_ms: new.audio_duration_ms,
+            latency_ms: new.latency_ms,
+            model: new.model,
+            profile_id: new.profile_id,
+            app: new.app,
+            text: new.text,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+
+        entries.push(entry.clone());
+        if self.prune(&mut entries) {
+            self.rewrite(&entries)?;
+        }
+        Ok(entry)
+    }
+
+    /// Newest first.
+    pub fn list(&self, limit: Option<usize>) -> Vec<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    pub fn ids(&self) -> Vec<u64> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().map(|entry| entry.id).collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().find(|entry| entry.id == id).cloned()
+    }
+
+    /// Case-insensitive substring match on the text, newest first.
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Vec<HistoryEntry> {
+        let query = query.to_lowercase();
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.text.to_lowercase().contains(&query))
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// Texts of the latest transcripts dictated into `app` within the
+    /// settings' window, oldest first.
+    pub fn recent_for_app(&self, app: &str, settings: &ContinuitySettings) -> Vec<String> {
+        let cutoff = now_ms().saturating_sub(settings.window_secs * 1000);
+        let entries = self.entries.lock().unwrap();
+        let mut texts: Vec<String> = entries
+            .iter()
+            .rev()
+            .take_while(|entry| entry.timestamp_ms >= cutoff)
+            .filter(|entry| entry.app.as_deref() == Some(app))
+            .take(settings.max_transcripts)
+            .map(|entry| entry.text.clone())
+            .collect();
+        texts.reverse();
+        texts
+    }
+
+    /// Remove the given entries and return how many were found.
+    pub fn delete(&self, ids: &[u64]) -> Result<usize, String> {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|entry| !ids.contains(&entry.id));
+        let removed = before - entries.len();
+        if removed > 0 {
+            self.rewrite(&entries)?;
+        }
+        Ok(removed)
+    }
+
+    pub fn set_retention(&self, retention: HistoryRetention) -> Result<(), String> {
+        *self.retention.lock().unwrap() = retention;
+        let mut entries = self.entries.lock().unwrap();
+        if self.prune(&mut entries) {
+            self.rewrite(&entries)?;
+        }
+        Ok(())
+    }
+
+    /// Drop entries outside the retention policy; returns whether any were.
+    fn prune(&self, entries: &mut Vec<HistoryEntry>) -> bool {
+        let retention = *self.retention.lock().unwrap();
+        let before = entries.len();
+
+        if let Some(days) = retention.max_age_days {
+            let max_age = Duration::from_secs(days * 24 * 60 * 60).as_millis() as u64;
+            let cutoff = now_ms().saturating_sub(max_age);
+            entries.retain(|entry| entry.timestamp_ms >= cutoff);
+        }
+        if let Some(max) = retention.max_entries {
+            let excess = entries.len().saturating_sub(max);
+            entries.drain(..excess);
+        }
+
+        entries.len() != before
+    }
+
+    /// Replace the file with `entries`, via a temporary file so a crash
+    /// never leaves it half-written. The newest entries may be among those
+    /// removed, so the last assigned ID is saved first.
+    fn rewrite(&self, entries: &[HistoryEntry]) -> Result<(), String> {
+        let last_id_path = last_id_path(&self.path);
+        let last_id = *self.last_id.lock().unwrap();
+        fs::write(&last_id_path, last_id.to_string())
+            .map_err(|e| format!("Failed to write {}: {}", last_id_path.display(), e))?;
+
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?);
+            content.push('\n');
+        }
+
+        json_file::write_atomic(&self.path, &content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_entry(text: &str) -> NewEntry {
+        NewEntry {
+            audio_duration_ms: 1000,
+            latency_ms: 500,
+            model: "gemini-2.5-flash".to_string(),
+            profile_id: "default".to_string(),
+            app: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn ids_are_not_reused_after_deleting_the_newest_entry() {
+        let dir = std::env::temp_dir().join(format!("history-ids-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("history.jsonl");
+
+        let store = HistoryStore::new(path.clone());
+        assert_eq!(store.add(new_entry("one")).unwrap().id, 1);
+        assert_eq!(store.add(new_entry("two")).unwrap().id, 2);
+        assert_eq!(store.delete(&[2]).unwrap(), 1);
+        assert_eq!(store.add(new_entry("three")).unwrap().id, 3);
+        assert_eq!(store.delete(&[3]).unwrap(), 1);
+
+        // The counter survives a restart
+        let store = HistoryStore::new(path);
+        assert_eq!(store.ids(), vec![1]);
+        assert_eq!(store.add(new_entry("four")).unwrap().id, 4);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Read a settings file, falling back to the default when it is missing or
+/// unreadable.
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> T {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse {}: {}", path.display(), e);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+/// Write `value` as pretty-printed JSON, creating the directory if needed.
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, &content)
+}
+
+/// Replace `path` with `content` via a temporary file, so a crash never
+/// leaves it half-written.
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, content)
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
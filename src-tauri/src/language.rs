@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+
+/// Language spoken in recordings. Serialized as its code so the setting
+/// reads the same in settings.json and tray menu ids.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Auto,
+    #[default]
+    Ja,
+    En,
+    /// Japanese with English words and sentences mixed in.
+    Mixed,
+    Zh,
+    Ko,
+    Es,
+    Fr,
+    De,
+}
+
+impl Language {
+    pub const ALL: [Language; 9] = [
+        Language::Auto,
+        Language::Ja,
+        Language::En,
+        Language::Mixed,
+        Language::Zh,
+        Language::Ko,
+        Language::Es,
+        Language::Fr,
+        Language::De,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Auto => "auto",
+            Language::Ja => "ja",
+            Language::En => "en",
+            Language::Mixed => "mixed",
+            Language::Zh => "zh",
+            Language::Ko => "ko",
+            Language::Es => "es",
+            Language::Fr => "fr",
+            Language::De => "de",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == code)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::Auto => "Auto-detect",
+            Language::Ja => "Japanese",
+            Language::En => "English",
+            Language::Mixed => "Japanese + English",
+            Language::Zh => "Chinese",
+            Language::Ko => "Korean",
+            Language::Es => "Spanish",
+            Language::Fr => "French",
+            Language::De => "German",
+        }
+    }
+
+    /// Name used in the (Japanese) prompt text.
+    fn prompt_name(self) -> Option<&'static str> {
+        match self {
+            Language::Auto | Language::Mixed => None,
+            Language::Ja => Some("日本語"),
+            Language::En => Some("英語"),
+            Language::Zh => Some("中国語"),
+            Language::Ko => Some("韓国語"),
+            Language::Es => Some("スペイン語"),
+            Language::Fr => Some("フランス語"),
+            Language::De => Some("ドイツ語"),
+        }
+    }
+
+    /// Hint appended to prompts without `{{language}}`, such as the
+    /// translation and email prompts or a transcription prompt copied
+    /// before the placeholder existed. It names only the spoken language,
+    /// since those prompts choose their own output language. `None` when
+    /// auto-detecting.
+    pub fn input_hint(self) -> Option<String> {
+        match self {
+            Language::Auto => None,
+            Language::Mixed => {
+                Some("入力音声は日本語と英語 (ja, en) が混在しています。".to_string())
+            }
+            _ => Some(format!(
+                "入力音声は{} ({}) です。",
+                self.prompt_name().unwrap_or_default(),
+                self.code()
+            )),
+        }
+    }
+
+    /// Sentence substituted for `{{language}}` in prompts. Gemini takes no
+    /// input-language parameter, so the language code goes in here as the
+    /// hint.
+    pub fn instruction(self) -> String {
+        match self {
+            Language::Auto => {
+                "入力音声の言語を聞き分け、翻訳せずに話された言語のまま出力してください。".to_string()
+            }
+            Language::Mixed => {
+                "入力音声は日本語と英語 (ja, en) が混在しています。翻訳せずに話された言語のまま出力してください。"
+                    .to_string()
+            }
+            _ => {
+                let name = self.prompt_name().unwrap_or_default();
+                format!(
+                    "入力音声は{} ({}) です。出力も{}でお願いします。",
+                    name,
+                    self.code(),
+                    name
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_hint_names_only_the_spoken_language() {
+        assert_eq!(Language::Auto.input_hint(), None);
+        assert_eq!(
+            Language::En.input_hint().as_deref(),
+            Some("入力音声は英語 (en) です。")
+        );
+        assert!(Language::Mixed.input_hint().unwrap().contains("(ja, en)"));
+        for language in Language::ALL {
+            let hint = language.input_hint().unwrap_or_default();
+            assert!(!hint.contains("出力"), "{}: {}", language.code(), hint);
+        }
+    }
+}
@@ -1,21 +1,60 @@
-use base64::Engine;
+mod accelerator;
+mod archive;
+mod gemini;
+mod focus;
+mod history;
+mod json_file;
+mod language;
+mod normalize;
+mod profiles;
+mod prompts;
+mod push_to_talk;
+mod rules;
+mod selection;
+mod shortcuts;
+mod status;
+mod template;
+mod voice_commands;
+mod vocabulary;
+
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use hound::{WavSpec, WavWriter};
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Cursor;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{TrayIcon, TrayIconBuilder},
-    AppHandle, Emitter, Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
 use tauri_plugin_clipboard_manager::ClipboardExt;
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 use tokio::sync::mpsc;
 use tiny_skia::{Pixmap, Paint, PathBuilder, Stroke, Transform};
 
+use archive::{ArchiveSettings, AudioArchive};
+use focus::{find_app_rule, frontmost_app, AppContextRule};
+use gemini::{
+    transcribe_with_gemini, transcribe_with_gemini_stream, GeminiConfig, GenerationSettings,
+    TranslationSettings,
+};
+use history::{ContinuitySettings, HistoryEntry, HistoryRetention, HistoryStore, NewEntry};
+use language::Language;
+use normalize::{normalize, NormalizeSettings};
+use profiles::{
+    default_profiles, find_profile, merge_profiles, OutputSink, Profile, DEFAULT_PROFILE,
+};
+use prompts::{watch_prompts, PromptStore};
+use push_to_talk::PushToTalkConfig;
+use rules::{ReplacementRule, RuleSet, RuleStore};
+use selection::selected_text;
+use shortcuts::{ShortcutAction, ShortcutBinding, ShortcutError};
+use status::{Animation, PartialTranscript, Status};
+use template::TemplateContext;
+use vocabulary::{VocabularyStore, VocabularyTerm};
+use voice_commands::{Processed, VoiceCommandSettings, VoiceCommandStore};
+
 #[cfg(target_os = "macos")]
 #[link(name = "ApplicationServices", kind = "framework")]
 extern "C" {
@@ -41,18 +80,43 @@ fn check_accessibility_permission() -> bool {
 
 // Application state
 struct AppState {
-    current_shortcut: Mutex<Option<Shortcut>>,
+    /// Registered global shortcuts by binding ID.
+    shortcuts: Mutex<HashMap<String, ShortcutBinding>>,
+    profiles: Mutex<Vec<Profile>>,
     audio_sender: Mutex<Option<mpsc::Sender<AudioCommand>>>,
     api_key: Mutex<String>,
     model: Mutex<String>,
+    generation: Mutex<GenerationSettings>,
+    use_system_instruction: Mutex<bool>,
+    translation: Mutex<TranslationSettings>,
+    /// Spoken language filled into prompts; switchable from the tray.
+    language: Mutex<Language>,
+    continuity: Mutex<ContinuitySettings>,
+    normalize: Mutex<NormalizeSettings>,
+    app_rules: Mutex<Vec<AppContextRule>>,
+    /// App that had focus when the last recording started.
+    last_focused_app: Mutex<Option<String>>,
+    streaming: Mutex<bool>,
+    type_progressively: Mutex<bool>,
     tray_icon: Mutex<Option<TrayIcon>>,
     animation_running: Arc<Mutex<bool>>,
-    current_animation: Mutex<Option<String>>,
+    current_animation: Mutex<Option<Animation>>,
+    last_transcript: Mutex<Option<String>>,
+    /// Characters the last job typed into the focused app, for "delete that".
+    last_inserted_chars: Mutex<usize>,
+    hands_free: Mutex<bool>,
+    cancel_requested: Mutex<bool>,
 }
 
 enum AudioCommand {
-    StartRecording,
+    /// With `edit_selection`, the recording is an instruction for rewriting
+    /// the selected text rather than text to insert.
+    StartRecording {
+        profile_id: String,
+        edit_selection: bool,
+    },
     StopRecording,
+    Cancel,
 }
 
 // Audio recording state
@@ -61,50 +125,6 @@ struct RecordingState {
     is_recording: bool,
 }
 
-// Gemini API types
-#[derive(Serialize)]
-struct GeminiRequest {
-    contents: Vec<Content>,
-}
-
-#[derive(Serialize)]
-struct Content {
-    parts: Vec<Part>,
-}
-
-#[derive(Serialize)]
-#[serde(untagged)]
-enum Part {
-    Text { text: String },
-    InlineData { inline_data: InlineData },
-}
-
-#[derive(Serialize)]
-struct InlineData {
-    mime_type: String,
-    data: String,
-}
-
-#[derive(Deserialize)]
-struct GeminiResponse {
-    candidates: Option<Vec<Candidate>>,
-}
-
-#[derive(Deserialize)]
-struct Candidate {
-    content: Option<CandidateContent>,
-}
-
-#[derive(Deserialize)]
-struct CandidateContent {
-    parts: Option<Vec<ResponsePart>>,
-}
-
-#[derive(Deserialize)]
-struct ResponsePart {
-    text: Option<String>,
-}
-
 #[tauri::command]
 fn execute_paste(_app: AppHandle) {
     use std::process::Command;
@@ -132,6 +152,217 @@ fn execute_paste(_app: AppHandle) {
     }
 }
 
+/// Press Backspace `count` times in the focused app.
+fn execute_backspace(count: usize) {
+    use std::process::Command;
+
+    let script = format!(
+        r#"
+        tell application "System Events"
+            repeat {} times
+                key code 51
+            end repeat
+        end tell
+    "#,
+        count
+    );
+
+    match Command::new("osascript").arg("-e").arg(script).output() {
+        Ok(o) if !o.status.success() => {
+            println!("Backspace Script Error: {}", String::from_utf8_lossy(&o.stderr));
+        }
+        Ok(_) => println!("Deleted {} characters", count),
+        Err(e) => println!("Failed to execute backspace command: {}", e),
+    }
+}
+
+/// Remove what the previous job typed, for the "delete that" command.
+fn delete_previous_insertion(app: &AppHandle) {
+    let count = std::mem::take(&mut *app.state::<AppState>().last_inserted_chars.lock().unwrap());
+    if count == 0 {
+        println!("Nothing to delete");
+        return;
+    }
+    execute_backspace(count);
+}
+
+/// Paste `text` off the calling thread.
+fn paste_in_background(app: &AppHandle, text: String) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = paste_text(&app, &text) {
+            eprintln!("{}", e);
+        }
+    });
+}
+
+/// Paste the most recent transcript again, falling back to history after
+/// a restart.
+fn paste_last_transcript(app: &AppHandle) {
+    let text = app
+        .state::<AppState>()
+        .last_transcript
+        .lock()
+        .unwrap()
+        .clone()
+        .or_else(|| {
+            app.state::<HistoryStore>()
+                .list(Some(1))
+                .pop()
+                .map(|entry| entry.text)
+        });
+    match text {
+        Some(text) => paste_in_background(app, text),
+        None => println!("No transcript to paste yet"),
+    }
+}
+
+/// Number of transcripts in the tray's Recent submenu.
+const RECENT_ITEMS: usize = 5;
+/// Longest transcript preview shown in the tray, in characters.
+const RECENT_PREVIEW_CHARS: usize = 40;
+
+fn preview(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= RECENT_PREVIEW_CHARS {
+        return line;
+    }
+    let truncated: String = line.chars().take(RECENT_PREVIEW_CHARS - 1).collect();
+    format!("{}…", truncated)
+}
+
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let recent = Submenu::with_id(app, "recent", "Recent", true)?;
+    let entries = app.state::<HistoryStore>().list(Some(RECENT_ITEMS));
+    if entries.is_empty() {
+        recent.append(&MenuItem::with_id(
+            app,
+            "recent-empty",
+            "No transcripts yet",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for entry in entries {
+        recent.append(&MenuItem::with_id(
+            app,
+            format!("recent:{}", entry.id),
+            preview(&entry.text),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    let current = *app.state::<AppState>().language.lock().unwrap();
+    let language = Submenu::with_id(app, "language", "Language", true)?;
+    for option in Language::ALL {
+        language.append(&CheckMenuItem::with_id(
+            app,
+            format!("language:{}", option.code()),
+            option.label(),
+            true,
+            option == current,
+            None::<&str>,
+        )?)?;
+    }
+
+    let paste_last = MenuItem::with_id(app, "paste-last", "Paste last again", true, None::<&str>)?;
+    let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
+    let history = MenuItem::with_id(app, "history", "History", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    Menu::with_items(
+        app,
+        &[
+            &recent,
+            &paste_last,
+            &PredefinedMenuItem::separator(app)?,
+            &language,
+            &settings,
+            &history,
+            &quit,
+        ],
+    )
+}
+
+/// Rebuild the tray menu so the Recent and Language submenus match the
+/// current state.
+fn refresh_tray_menu(app: &AppHandle) {
+    let menu = match build_tray_menu(app) {
+        Ok(menu) => menu,
+        Err(e) => {
+            eprintln!("Failed to build tray menu: {}", e);
+            return;
+        }
+    };
+    if let Some(tray) = app.state::<AppState>().tray_icon.lock().unwrap().as_ref() {
+        if let Err(e) = tray.set_menu(Some(menu)) {
+            eprintln!("Failed to update tray menu: {}", e);
+        }
+    }
+}
+
+fn handle_tray_menu(app: &AppHandle, id: &str) {
+    match id {
+        "quit" => {
+            app.exit(0);
+        }
+        "settings" => {
+            show_main_window(app);
+        }
+        "history" => {
+            show_history(app);
+        }
+        "paste-last" => {
+            paste_last_transcript(app);
+        }
+        _ if id.starts_with("language:") => {
+            // The settings window persists the choice
+            if let Some(language) = id.strip_prefix("language:").and_then(Language::from_code) {
+                set_language(app.clone(), language);
+                let _ = app.emit("language-changed", language);
+            }
+        }
+        _ => {
+            // Recent items: copy and paste that transcript again
+            let entry = id
+                .strip_prefix("recent:")
+                .and_then(|id| id.parse().ok())
+                .and_then(|id| app.state::<HistoryStore>().get(id));
+            if let Some(entry) = entry {
+                paste_in_background(app, entry.text);
+            }
+        }
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.center();
+        let _ = window.set_decorations(true);
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Show the settings window scrolled to the history list.
+fn show_history(app: &AppHandle) {
+    show_main_window(app);
+    let _ = app.emit("open-history", ());
+}
+
+/// Put `text` on the clipboard and paste it into the focused app.
+fn paste_text(app: &AppHandle, text: &str) -> Result<(), String> {
+    app.clipboard()
+        .write_text(text.to_string())
+        .map_err(|e| format!("Clipboard error: {}", e))?;
+
+    // Small delay
+    std::thread::sleep(Duration::from_millis(100));
+
+    execute_paste(app.clone());
+    Ok(())
+}
+
 #[tauri::command]
 fn set_api_key(app: AppHandle, api_key: String) {
     let state = app.state::<AppState>();
@@ -147,121 +378,402 @@ fn set_model(app: AppHandle, model: String) {
 }
 
 #[tauri::command]
-fn register_shortcut(app: AppHandle, shortcut_str: String) -> Result<(), String> {
+fn set_generation_config(app: AppHandle, config: GenerationSettings) {
     let state = app.state::<AppState>();
+    *state.generation.lock().unwrap() = config;
+    println!("Generation config updated");
+}
 
-    // Unregister previous shortcut
-    if let Some(old_shortcut) = state.current_shortcut.lock().unwrap().take() {
-        let _ = app.global_shortcut().unregister(old_shortcut);
-    }
+#[tauri::command]
+fn set_use_system_instruction(app: AppHandle, enabled: bool) {
+    let state = app.state::<AppState>();
+    *state.use_system_instruction.lock().unwrap() = enabled;
+    println!("System instruction setting updated");
+}
 
-    // Parse the shortcut string
-    let shortcut = parse_shortcut(&shortcut_str).map_err(|e| e.to_string())?;
+#[tauri::command]
+fn set_translation_settings(app: AppHandle, settings: TranslationSettings) {
+    println!(
+        "Translation settings updated: {} -> {}",
+        settings.source_language, settings.target_language
+    );
+    *app.state::<AppState>().translation.lock().unwrap() = settings;
+}
 
-    // Get audio sender
-    let sender = state.audio_sender.lock().unwrap().clone();
+#[tauri::command]
+fn set_language(app: AppHandle, language: Language) {
+    *app.state::<AppState>().language.lock().unwrap() = language;
+    println!("Language set to {}", language.code());
+    refresh_tray_menu(&app);
+}
 
-    // Register new shortcut
-    app.global_shortcut()
-        .on_shortcut(shortcut.clone(), move |_app, _shortcut, event| {
-            if let Some(ref tx) = sender {
-                match event.state {
-                    ShortcutState::Pressed => {
-                        println!("Shortcut pressed - starting recording");
-                        let _ = tx.blocking_send(AudioCommand::StartRecording);
-                    }
-                    ShortcutState::Released => {
-                        println!("Shortcut released - stopping recording");
-                        let _ = tx.blocking_send(AudioCommand::StopRecording);
-                    }
-                }
-            }
-        })
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+fn set_continuity_settings(app: AppHandle, settings: ContinuitySettings) {
+    *app.state::<AppState>().continuity.lock().unwrap() = settings;
+    println!("Continuity settings updated: {:?}", settings);
+}
+
+#[tauri::command]
+fn set_normalize_settings(app: AppHandle, settings: NormalizeSettings) {
+    *app.state::<AppState>().normalize.lock().unwrap() = settings;
+    println!("Normalization settings updated: {:?}", settings);
+}
 
-    // Store the shortcut
-    *state.current_shortcut.lock().unwrap() = Some(shortcut);
+#[tauri::command]
+fn set_app_rules(app: AppHandle, rules: Vec<AppContextRule>) {
+    *app.state::<AppState>().app_rules.lock().unwrap() = rules;
+    println!("App rules updated");
+}
 
-    println!("Registered shortcut: {}", shortcut_str);
+/// The app that was focused when the last recording started, so the
+/// settings window can offer it as a rule target.
+#[tauri::command]
+fn get_last_focused_app(app: AppHandle) -> Option<String> {
+    app.state::<AppState>().last_focused_app.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_prompt(app: AppHandle, name: String) -> Result<String, String> {
+    app.state::<PromptStore>().get(&name)
+}
+
+/// Check a prompt for unknown `{{variables}}` without saving it.
+#[tauri::command]
+fn validate_prompt(text: String) -> Result<(), String> {
+    template::validate(&text)
+}
+
+#[tauri::command]
+fn set_prompt(app: AppHandle, name: String, text: String) -> Result<(), String> {
+    template::validate(&text)?;
+    app.state::<PromptStore>().set(&name, &text)?;
+    println!("Prompt updated: {}", name);
     Ok(())
 }
 
-fn parse_shortcut(s: &str) -> Result<Shortcut, String> {
-    let parts: Vec<&str> = s.split('+').collect();
-    let mut modifiers = Modifiers::empty();
-    let mut code: Option<Code> = None;
+#[tauri::command]
+fn set_streaming(app: AppHandle, streaming: bool, type_progressively: bool) {
+    let state = app.state::<AppState>();
+    *state.streaming.lock().unwrap() = streaming;
+    *state.type_progressively.lock().unwrap() = type_progressively;
+    println!("Streaming updated");
+}
 
-    for part in parts {
-        match part.to_uppercase().as_str() {
-            "COMMANDORCONTROL" | "CMD" | "COMMAND" | "CTRL" | "CONTROL" => {
-                modifiers |= Modifiers::META;
-            }
-            "SHIFT" => {
-                modifiers |= Modifiers::SHIFT;
-            }
-            "ALT" | "OPTION" => {
-                modifiers |= Modifiers::ALT;
-            }
-            key => {
-                code = Some(match key {
-                    "SPACE" | " " => Code::Space,
-                    "A" => Code::KeyA,
-                    "B" => Code::KeyB,
-                    "C" => Code::KeyC,
-                    "D" => Code::KeyD,
-                    "E" => Code::KeyE,
-                    "F" => Code::KeyF,
-                    "G" => Code::KeyG,
-                    "H" => Code::KeyH,
-                    "I" => Code::KeyI,
-                    "J" => Code::KeyJ,
-                    "K" => Code::KeyK,
-                    "L" => Code::KeyL,
-                    "M" => Code::KeyM,
-                    "N" => Code::KeyN,
-                    "O" => Code::KeyO,
-                    "P" => Code::KeyP,
-                    "Q" => Code::KeyQ,
-                    "R" => Code::KeyR,
-                    "S" => Code::KeyS,
-                    "T" => Code::KeyT,
-                    "U" => Code::KeyU,
-                    "V" => Code::KeyV,
-                    "W" => Code::KeyW,
-                    "X" => Code::KeyX,
-                    "Y" => Code::KeyY,
-                    "Z" => Code::KeyZ,
-                    "0" => Code::Digit0,
-                    "1" => Code::Digit1,
-                    "2" => Code::Digit2,
-                    "3" => Code::Digit3,
-                    "4" => Code::Digit4,
-                    "5" => Code::Digit5,
-                    "6" => Code::Digit6,
-                    "7" => Code::Digit7,
-                    "8" => Code::Digit8,
-                    "9" => Code::Digit9,
-                    "HOME" => Code::Home,
-                    "END" => Code::End,
-                    "PAGEUP" => Code::PageUp,
-                    "PAGEDOWN" => Code::PageDown,
-                    "UP" => Code::ArrowUp,
-                    "DOWN" => Code::ArrowDown,
-                    "LEFT" => Code::ArrowLeft,
-                    "RIGHT" => Code::ArrowRight,
-                    "ENTER" => Code::Enter,
-                    "ESCAPE" => Code::Escape,
-                    "TAB" => Code::Tab,
-                    "BACKSPACE" => Code::Backspace,
-                    "DELETE" => Code::Delete,
-                    _ => return Err(format!("Unknown key: {}", key)),
-                });
-            }
+#[tauri::command]
+fn get_vocabulary(app: AppHandle) -> Vec<VocabularyTerm> {
+    app.state::<VocabularyStore>().get()
+}
+
+#[tauri::command]
+fn set_vocabulary(app: AppHandle, terms: Vec<VocabularyTerm>) -> Result<(), String> {
+    app.state::<VocabularyStore>().set(terms)
+}
+
+#[tauri::command]
+fn get_rules(app: AppHandle) -> Vec<ReplacementRule> {
+    app.state::<RuleStore>().get()
+}
+
+#[tauri::command]
+fn set_rules(app: AppHandle, rules: Vec<ReplacementRule>) -> Result<(), String> {
+    app.state::<RuleStore>().set(rules)
+}
+
+/// Run `rules` (not necessarily saved yet) over `text` for previewing.
+#[tauri::command]
+fn test_rules(
+    rules: Vec<ReplacementRule>,
+    text: String,
+    profile_id: Option<String>,
+) -> Result<String, String> {
+    let profile_id = profile_id.unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    Ok(RuleSet::compile(&rules)?.apply(&text, &profile_id, None))
+}
+
+#[tauri::command]
+fn get_voice_commands(app: AppHandle) -> VoiceCommandSettings {
+    app.state::<VoiceCommandStore>().get()
+}
+
+#[tauri::command]
+fn set_voice_commands(app: AppHandle, settings: VoiceCommandSettings) -> Result<(), String> {
+    app.state::<VoiceCommandStore>().set(settings)
+}
+
+#[tauri::command]
+fn get_profiles(app: AppHandle) -> Vec<Profile> {
+    let state = app.state::<AppState>();
+    let profiles = state.profiles.lock().unwrap().clone();
+    profiles
+}
+
+#[tauri::command]
+fn get_default_profiles() -> Vec<Profile> {
+    default_profiles()
+}
+
+/// Replace the profiles with `profiles` merged over the built-in ones.
+#[tauri::command]
+fn set_profiles(app: AppHandle, profiles: Vec<Profile>) -> Result<(), String> {
+    let profiles = merge_profiles(profiles);
+    for profile in &profiles {
+        if profile.id.is_empty() || profile.name.trim().is_empty() {
+            return Err("Every profile needs an ID and a name".to_string());
         }
+        prompts::validate_name(&profile.prompt)
+            .map_err(|e| format!("Profile {}: {}", profile.name, e))?;
     }
 
-    let code = code.ok_or("No key specified")?;
-    Ok(Shortcut::new(Some(modifiers), code))
+    let state = app.state::<AppState>();
+    *state.profiles.lock().unwrap() = profiles;
+    println!("Profiles updated");
+    Ok(())
+}
+
+/// Bind `shortcut_str` to `action` under the binding `id`. Several
+/// bindings can be active at once; re-using an ID replaces its shortcut.
+#[tauri::command]
+fn register_shortcut(
+    app: AppHandle,
+    id: String,
+    shortcut_str: String,
+    action: ShortcutAction,
+) -> Result<(), ShortcutError> {
+    shortcuts::register(&app, &id, &shortcut_str, action)
+}
+
+#[tauri::command]
+fn unregister_shortcut(app: AppHandle, id: String) -> Result<(), ShortcutError> {
+    shortcuts::unregister(&app, &id)
+}
+
+#[tauri::command]
+fn list_shortcuts(app: AppHandle) -> Vec<ShortcutBinding> {
+    shortcuts::list(&app)
+}
+
+/// Add a transcript to the history, keep its recording when `archive` is
+/// set, and refresh everything that shows history.
+fn record_history(
+    app: &AppHandle,
+    entry: NewEntry,
+    wav: &[u8],
+    archive: bool,
+) -> Result<HistoryEntry, String> {
+    let history = app.state::<HistoryStore>();
+    let entry = history.add(entry)?;
+
+    let recordings = app.state::<AudioArchive>();
+    if archive {
+        if let Err(e) = recordings.save(entry.id, wav) {
+            eprintln!("Failed to archive recording: {}", e);
+        }
+    }
+    recordings.prune(&history.ids());
+
+    let _ = app.emit("history-changed", ());
+    refresh_tray_menu(app);
+    Ok(entry)
+}
+
+/// Turn a raw transcript into the text to deliver: replacement rules, then
+/// spoken commands (for profiles that take them), then style
+/// normalization. `focused_app` selects app-specific rules and text style.
+fn post_process(
+    app: &AppHandle,
+    text: &str,
+    profile: &Profile,
+    focused_app: Option<&str>,
+) -> Processed {
+    let text = app
+        .state::<RuleStore>()
+        .apply(text, &profile.id, focused_app);
+    let processed = if profile.voice_commands() {
+        app.state::<VoiceCommandStore>().process(&text)
+    } else {
+        Processed {
+            text,
+            delete_previous: false,
+        }
+    };
+    let settings = {
+        let state = app.state::<AppState>();
+        let app_rules = state.app_rules.lock().unwrap();
+        focused_app
+            .and_then(|focused| find_app_rule(&app_rules, focused))
+            .and_then(|rule| rule.normalize)
+            .unwrap_or_else(|| *state.normalize.lock().unwrap())
+    };
+    Processed {
+        text: normalize(&processed.text, &settings),
+        ..processed
+    }
+}
+
+/// Settings shared by the live pipeline and re-transcription. The profile's
+/// prompt is rendered here so every request gets the same variables; the
+/// spoken language, dictionary and selection are still sent when the prompt
+/// doesn't place them itself. With continuity on, recent transcripts from
+/// `focused_app` go along as context.
+fn gemini_config(
+    app: &AppHandle,
+    profile: &Profile,
+    focused_app: Option<&str>,
+    selection: Option<String>,
+) -> Result<GeminiConfig, String> {
+    let state = app.state::<AppState>();
+    let vocabulary = app.state::<VocabularyStore>();
+    let template = app.state::<PromptStore>().get(&profile.prompt)?;
+    let language = *state.language.lock().unwrap();
+    let context = TemplateContext {
+        language: language.instruction(),
+        vocabulary: vocabulary.glossary(),
+        app_name: focused_app.unwrap_or_default().to_string(),
+        date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        selection: selection.clone().unwrap_or_default(),
+        previous_transcript: state
+            .last_transcript
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_default(),
+    };
+    let prompt = template::render(&template, &context)
+        .map_err(|e| format!("Prompt {}: {}", profile.prompt, e))?;
+    let prompt = match language.input_hint() {
+        Some(hint) if !template::uses(&template, "language") => {
+            prompt.trim_end().to_string() + "\n" + &hint
+        }
+        _ => prompt,
+    };
+    let prompt = if template::uses(&template, "vocabulary") {
+        prompt
+    } else {
+        vocabulary.apply(prompt)
+    };
+    let continuity = *state.continuity.lock().unwrap();
+    let context = match focused_app {
+        Some(focused) if continuity.enabled => app
+            .state::<HistoryStore>()
+            .recent_for_app(focused, &continuity),
+        _ => Vec::new(),
+    };
+
+    Ok(GeminiConfig {
+        api_key: state.api_key.lock().unwrap().clone(),
+        model: profile
+            .model
+            .clone()
+            .filter(|model| !model.is_empty())
+            .unwrap_or_else(|| state.model.lock().unwrap().clone()),
+        generation: state.generation.lock().unwrap().clone(),
+        prompt,
+        use_system_instruction: *state.use_system_instruction.lock().unwrap(),
+        selection: selection.filter(|_| !template::uses(&template, "selection")),
+        translation: profile
+            .translate
+            .then(|| state.translation.lock().unwrap().clone()),
+        context,
+    })
+}
+
+#[tauri::command]
+fn list_history(app: AppHandle, limit: Option<usize>) -> Vec<HistoryEntry> {
+    app.state::<HistoryStore>().list(limit)
+}
+
+#[tauri::command]
+fn search_history(app: AppHandle, query: String, limit: Option<usize>) -> Vec<HistoryEntry> {
+    app.state::<HistoryStore>().search(&query, limit)
+}
+
+#[tauri::command]
+fn delete_history(app: AppHandle, ids: Vec<u64>) -> Result<usize, String> {
+    let history = app.state::<HistoryStore>();
+    let removed = history.delete(&ids)?;
+    app.state::<AudioArchive>().prune(&history.ids());
+    let _ = app.emit("history-changed", ());
+    refresh_tray_menu(&app);
+    Ok(removed)
+}
+
+#[tauri::command]
+fn set_history_retention(app: AppHandle, retention: HistoryRetention) -> Result<(), String> {
+    let history = app.state::<HistoryStore>();
+    history.set_retention(retention)?;
+    app.state::<AudioArchive>().prune(&history.ids());
+    let _ = app.emit("history-changed", ());
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_archive_settings(app: AppHandle, settings: ArchiveSettings) {
+    let archive = app.state::<AudioArchive>();
+    archive.set_settings(settings);
+    archive.prune(&app.state::<HistoryStore>().ids());
+}
+
+#[tauri::command]
+fn list_archived_recordings(app: AppHandle) -> Vec<u64> {
+    app.state::<AudioArchive>().archived_ids()
+}
+
+/// Transcribe an archived recording again, optionally with another profile
+/// or model, and add the result to the history as a new entry.
+#[tauri::command]
+async fn retranscribe(
+    app: AppHandle,
+    id: u64,
+    profile_id: Option<String>,
+    model: Option<String>,
+) -> Result<HistoryEntry, String> {
+    let original = app
+        .state::<HistoryStore>()
+        .get(id)
+        .ok_or_else(|| format!("No history entry {}", id))?;
+    let wav = app.state::<AudioArchive>().load(id)?;
+
+    let profile = {
+        let state = app.state::<AppState>();
+        let profiles = state.profiles.lock().unwrap();
+        find_profile(&profiles, profile_id.as_deref().unwrap_or(&original.profile_id))
+    };
+    let mut config = gemini_config(&app, &profile, None, None)?;
+    if let Some(model) = model.filter(|model| !model.is_empty()) {
+        config.model = model;
+    }
+    if config.api_key.is_empty() {
+        return Err("No API key set".to_string());
+    }
+
+    println!("Re-transcribing entry {} with {} ({})", id, config.model, profile.name);
+    let started_at = Instant::now();
+    let text = transcribe_with_gemini(&config, &wav)
+        .await
+        .map_err(|e| e.to_string())?;
+    let text = post_process(&app, &text, &profile, None).text;
+
+    // Keep a copy of the recording only while archiving is on
+    let archive = app.state::<AudioArchive>().enabled();
+    record_history(
+        &app,
+        NewEntry {
+            audio_duration_ms: original.audio_duration_ms,
+            latency_ms: elapsed_ms(started_at),
+            model: config.model,
+            profile_id: profile.id,
+            app: original.app,
+            text,
+        },
+        &wav,
+        archive,
+    )
+}
+
+#[tauri::command]
+fn set_push_to_talk(app: AppHandle, config: PushToTalkConfig) -> Result<(), String> {
+    push_to_talk::configure(&app, config)
 }
 
 // Generate animated icon frames
@@ -356,17 +868,12 @@ fn create_transcribing_icon(frame: u8) -> Vec<u8> {
     pixmap.encode_png().unwrap()
 }
 
-fn start_icon_animation(app: AppHandle, animation_type: &str) {
+fn start_icon_animation(app: AppHandle, animation: Animation) {
     let state = app.state::<AppState>();
 
     // Check if the same animation is already running
-    {
-        let current = state.current_animation.lock().unwrap();
-        if let Some(ref current_type) = *current {
-            if current_type == animation_type {
-                return; // Same animation already running, skip
-            }
-        }
+    if *state.current_animation.lock().unwrap() == Some(animation) {
+        return; // Same animation already running, skip
     }
 
     // Stop any existing animation
@@ -381,23 +888,20 @@ fn start_icon_animation(app: AppHandle, animation_type: &str) {
     }
 
     // Set current animation type
-    *state.current_animation.lock().unwrap() = Some(animation_type.to_string());
+    *state.current_animation.lock().unwrap() = Some(animation);
 
     // Start new animation
     let animation_running = Arc::clone(&state.animation_running);
     *animation_running.lock().unwrap() = true;
 
-    let animation_type = animation_type.to_string();
-
     std::thread::spawn(move || {
         let mut frame: u8 = 0;
 
         while *animation_running.lock().unwrap() {
-            let icon_data = match animation_type.as_str() {
-                "recording" => create_recording_icon(frame),
-                "processing" => create_processing_icon(frame),
-                "transcribing" => create_transcribing_icon(frame),
-                _ => continue,
+            let icon_data = match animation {
+                Animation::Recording => create_recording_icon(frame),
+                Animation::Processing => create_processing_icon(frame),
+                Animation::Transcribing => create_transcribing_icon(frame),
             };
 
             // Update tray icon
@@ -438,43 +942,53 @@ fn stop_icon_animation(app: &AppHandle) {
     }
 }
 
-fn update_tray_status(app: &AppHandle, status: &str) {
-    println!("Updating tray status to: {}", status);
+/// Update the tray icon and tooltip, and notify the settings window.
+fn set_status(app: &AppHandle, status: Status) {
+    println!("Updating status to: {:?}", status);
 
-    match status {
-        "recording" => {
-            start_icon_animation(app.clone(), "recording");
-        }
-        "processing" => {
-            start_icon_animation(app.clone(), "processing");
-        }
-        "transcribing" => {
-            start_icon_animation(app.clone(), "transcribing");
-        }
-        "success" | "error" | "idle" | _ => {
-            stop_icon_animation(app);
-        }
+    match status.animation() {
+        Some(animation) => start_icon_animation(app.clone(), animation),
+        None => stop_icon_animation(app),
     }
 
     // Update tooltip
     let state = app.state::<AppState>();
-    let tray_lock = state.tray_icon.lock().unwrap();
-    if let Some(tray) = tray_lock.as_ref() {
-        let tooltip = match status {
-            "recording" => "AquaVoice - Recording...",
-            "processing" => "AquaVoice - Processing...",
-            "transcribing" => "AquaVoice - Transcribing...",
-            "success" => "AquaVoice - Done",
-            "error" => "AquaVoice - Error",
-            _ => "AquaVoice - Ready",
-        };
-        let _ = tray.set_tooltip(Some(tooltip));
+    {
+        let tray_lock = state.tray_icon.lock().unwrap();
+        if let Some(tray) = tray_lock.as_ref() {
+            let _ = tray.set_tooltip(Some(status.tooltip()));
+        }
     }
+
+    let _ = app.emit("status-changed", status);
+}
+
+/// Show an error for a couple of seconds, then fall back to idle.
+fn report_error(app: &AppHandle, job_id: Option<u64>, started_at: Option<Instant>, message: String) {
+    set_status(
+        app,
+        Status::Error {
+            job_id,
+            elapsed_ms: started_at.map(elapsed_ms),
+            message,
+        },
+    );
+    std::thread::sleep(Duration::from_secs(2));
+    set_status(app, Status::Idle);
+}
+
+fn cancelled(app: &AppHandle) -> bool {
+    *app.state::<AppState>().cancel_requested.lock().unwrap()
+}
+
+fn elapsed_ms(since: Instant) -> u64 {
+    since.elapsed().as_millis() as u64
 }
 
-fn samples_to_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, String> {
+/// `samples` are interleaved when there is more than one channel.
+fn samples_to_wav(samples: &[f32], sample_rate: u32, channels: u16) -> Result<Vec<u8>, String> {
     let spec = WavSpec {
-        channels: 1,
+        channels,
         sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
@@ -499,66 +1013,6 @@ fn samples_to_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, String>
     Ok(cursor.into_inner())
 }
 
-async fn transcribe_with_gemini(api_key: &str, model: &str, audio_data: &[u8]) -> Result<String, String> {
-    let base64_audio = base64::engine::general_purpose::STANDARD.encode(audio_data);
-
-    // Load transcription prompt from file
-    const TRANSCRIPTION_PROMPT: &str = include_str!("../prompts/transcription.txt");
-
-    let request = GeminiRequest {
-        contents: vec![Content {
-            parts: vec![
-                Part::InlineData {
-                    inline_data: InlineData {
-                        mime_type: "audio/wav".to_string(),
-                        data: base64_audio,
-                    },
-                },
-                Part::Text {
-                    text: TRANSCRIPTION_PROMPT.to_string(),
-                },
-            ],
-        }],
-    };
-
-    let client = reqwest::Client::new();
-    let url = format!(
-        "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-        model, api_key
-    );
-
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("API error: {}", error_text));
-    }
-
-    let gemini_response: GeminiResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("JSON parse error: {}", e))?;
-
-    let text = gemini_response
-        .candidates
-        .and_then(|c| c.into_iter().next())
-        .and_then(|c| c.content)
-        .and_then(|c| c.parts)
-        .and_then(|p| p.into_iter().next())
-        .and_then(|p| p.text)
-        .unwrap_or_default();
-
-    Ok(text.trim().to_string())
-}
-
 fn start_audio_processing(app: AppHandle, mut rx: mpsc::Receiver<AudioCommand>) {
     std::thread::spawn(move || {
         let host = cpal::default_host();
@@ -568,9 +1022,10 @@ fn start_audio_processing(app: AppHandle, mut rx: mpsc::Receiver<AudioCommand>)
 
         let config = device.default_input_config().expect("No default config");
         let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
 
         println!("Using audio device: {}", device.name().unwrap_or_default());
-        println!("Sample rate: {}", sample_rate);
+        println!("Sample rate: {}, channels: {}", sample_rate, channels);
 
         let recording_state = Arc::new(Mutex::new(RecordingState {
             samples: Vec::new(),
@@ -597,109 +1052,293 @@ fn start_audio_processing(app: AppHandle, mut rx: mpsc::Receiver<AudioCommand>)
 
         let rt = tokio::runtime::Runtime::new().unwrap();
 
+        let mut job_id: u64 = 0;
+        let mut started_at = Instant::now();
+        let mut job_profile = DEFAULT_PROFILE.to_string();
+        let mut job_app: Option<String> = None;
+        let mut job_edits_selection = false;
+
         loop {
             match rx.blocking_recv() {
-                Some(AudioCommand::StartRecording) => {
+                Some(AudioCommand::StartRecording {
+                    profile_id,
+                    edit_selection,
+                }) => {
                     println!("Starting recording...");
-                    let mut state = recording_state.lock().unwrap();
-                    state.samples.clear();
-                    state.is_recording = true;
-                    update_tray_status(&app, "recording");
-                    let _ = app.emit("status-changed", "recording");
+                    job_id += 1;
+                    started_at = Instant::now();
+                    job_profile = profile_id;
+                    job_edits_selection = edit_selection;
+                    *app.state::<AppState>().cancel_requested.lock().unwrap() = false;
+                    {
+                        let mut state = recording_state.lock().unwrap();
+                        state.samples.clear();
+                        state.is_recording = true;
+                    }
+                    set_status(&app, Status::Recording { job_id });
+
+                    // Looked up after recording starts so the first words aren't lost
+                    job_app = frontmost_app();
+                    println!("Focused app: {:?}", job_app);
+                    *app.state::<AppState>().last_focused_app.lock().unwrap() = job_app.clone();
+                }
+                Some(AudioCommand::Cancel) => {
+                    let was_recording = {
+                        let mut state = recording_state.lock().unwrap();
+                        let was_recording = state.is_recording;
+                        state.is_recording = false;
+                        state.samples.clear();
+                        was_recording
+                    };
+                    if was_recording {
+                        println!("Recording cancelled");
+                        set_status(&app, Status::Idle);
+                    }
                 }
                 Some(AudioCommand::StopRecording) => {
-                    println!("Stopping recording...");
-                    update_tray_status(&app, "processing");
-                    let _ = app.emit("status-changed", "processing");
                     let samples: Vec<f32>;
                     {
                         let mut state = recording_state.lock().unwrap();
+                        if !state.is_recording {
+                            // Already cancelled
+                            continue;
+                        }
+                        println!("Stopping recording...");
                         state.is_recording = false;
                         samples = state.samples.clone();
                     }
+                    // Latency counts from the release, not from the start of recording
+                    let stopped_at = Instant::now();
+                    // Samples from every channel are interleaved
+                    let audio_duration_ms =
+                        samples.len() as u64 * 1000 / (sample_rate as u64 * channels as u64);
+                    set_status(
+                        &app,
+                        Status::Processing {
+                            job_id,
+                            elapsed_ms: elapsed_ms(started_at),
+                            audio_duration_ms,
+                        },
+                    );
 
                     if samples.is_empty() {
                         println!("No audio recorded");
-                        update_tray_status(&app, "error");
-                        let _ = app.emit("status-changed", "error:No audio recorded");
-                        std::thread::sleep(std::time::Duration::from_secs(2));
-                        update_tray_status(&app, "idle");
+                        report_error(&app, Some(job_id), Some(started_at), "No audio recorded".to_string());
                         continue;
                     }
 
                     println!("Recorded {} samples", samples.len());
 
+                    // Read the selection now rather than on press, when the
+                    // shortcut's modifiers would turn the copy into another chord
+                    let selection = if job_edits_selection {
+                        match selected_text(&app) {
+                            Some(text) => Some(text),
+                            None => {
+                                println!("No text selected");
+                                report_error(
+                                    &app,
+                                    Some(job_id),
+                                    Some(started_at),
+                                    "No text selected".to_string(),
+                                );
+                                continue;
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
                     // Convert to WAV
-                    let wav_data = match samples_to_wav(&samples, sample_rate) {
+                    let wav_data = match samples_to_wav(&samples, sample_rate, channels) {
                         Ok(data) => data,
                         Err(e) => {
                             eprintln!("WAV conversion error: {}", e);
+                            report_error(&app, Some(job_id), Some(started_at), e);
                             continue;
                         }
                     };
 
                     println!("WAV data size: {} bytes", wav_data.len());
 
-                    // Get API key and model
-                    let (api_key, model): (String, String) = {
+                    // An app rule picks the profile unless the shortcut chose one
+                    let profile = {
                         let state = app.state::<AppState>();
-                        let api_key = state.api_key.lock().unwrap().clone();
-                        let model = state.model.lock().unwrap().clone();
-                        (api_key, model)
+                        let app_rules = state.app_rules.lock().unwrap();
+                        let app_profile = job_app
+                            .as_deref()
+                            .and_then(|focused| find_app_rule(&app_rules, focused))
+                            .and_then(|rule| rule.profile_id.clone())
+                            .filter(|_| job_profile == DEFAULT_PROFILE);
+                        let profiles = state.profiles.lock().unwrap();
+                        find_profile(&profiles, app_profile.as_deref().unwrap_or(&job_profile))
+                    };
+                    println!("Using profile: {}", profile.name);
+
+                    let config = match gemini_config(&app, &profile, job_app.as_deref(), selection)
+                    {
+                        Ok(config) => config,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            report_error(&app, Some(job_id), Some(started_at), e);
+                            continue;
+                        }
                     };
 
-                    if api_key.is_empty() {
+                    // Get streaming options
+                    let (streaming, type_progressively) = {
+                        let state = app.state::<AppState>();
+                        let streaming = *state.streaming.lock().unwrap();
+                        let type_progressively = *state.type_progressively.lock().unwrap()
+                            && profile.output == OutputSink::Paste;
+                        (streaming, type_progressively)
+                    };
+
+                    if config.api_key.is_empty() {
                         eprintln!("No API key set");
+                        report_error(&app, Some(job_id), Some(started_at), "No API key set".to_string());
                         continue;
                     }
 
-                    if model.is_empty() {
+                    if config.model.is_empty() {
                         eprintln!("No model set");
+                        report_error(&app, Some(job_id), Some(started_at), "No model set".to_string());
                         continue;
                     }
 
                     // Transcribe with Gemini
-                    let app_clone = app.clone();
-                    update_tray_status(&app, "transcribing");
-                    let _ = app.emit("status-changed", "transcribing");
-                    rt.block_on(async {
-                        match transcribe_with_gemini(&api_key, &model, &wav_data).await {
-                            Ok(text) => {
-                                println!("Transcription result: {}", text);
-
-                                if !text.is_empty() {
-                                    // Copy to clipboard
-                                    if let Err(e) =
-                                        app_clone.clipboard().write_text(text.clone())
-                                    {
-                                        eprintln!("Clipboard error: {}", e);
-                                        return;
-                                    }
-
-                                    // Small delay
-                                    tokio::time::sleep(tokio::time::Duration::from_millis(100))
-                                        .await;
-
-                                    // Paste
-                                    execute_paste(app_clone.clone());
-
-                                    update_tray_status(&app_clone, "success");
-                                    let _ = app_clone.emit("status-changed", "success");
-                                    std::thread::sleep(std::time::Duration::from_secs(2));
-                                    update_tray_status(&app_clone, "idle");
-                                    let _ = app_clone.emit("status-changed", "idle");
+                    let byte_size = wav_data.len();
+                    set_status(
+                        &app,
+                        Status::Transcribing {
+                            job_id,
+                            elapsed_ms: elapsed_ms(started_at),
+                            audio_duration_ms,
+                            byte_size,
+                        },
+                    );
+
+                    let mut typed_chars = 0;
+                    let result = rt.block_on(async {
+                        if !streaming {
+                            return transcribe_with_gemini(&config, &wav_data).await;
+                        }
+
+                        let mut partial = String::new();
+                        transcribe_with_gemini_stream(&config, &wav_data, |delta| {
+                            partial.push_str(delta);
+                            let _ = app.emit(
+                                "transcript-partial",
+                                PartialTranscript {
+                                    job_id,
+                                    delta: delta.to_string(),
+                                    text: partial.clone(),
+                                },
+                            );
+
+                            if type_progressively && !cancelled(&app) {
+                                match paste_text(&app, delta) {
+                                    Ok(()) => typed_chars += delta.chars().count(),
+                                    Err(e) => eprintln!("{}", e),
                                 }
                             }
-                            Err(e) => {
-                                eprintln!("Transcription error: {}", e);
-                                update_tray_status(&app_clone, "error");
-                                let _ = app_clone.emit("status-changed", format!("error:{}", e));
-                                std::thread::sleep(std::time::Duration::from_secs(2));
-                                update_tray_status(&app_clone, "idle");
-                                let _ = app_clone.emit("status-changed", "idle");
-                            }
-                        }
+                        })
+                        .await
                     });
+
+                    if cancelled(&app) {
+                        println!("Transcription cancelled");
+                        set_status(&app, Status::Idle);
+                        continue;
+                    }
+
+                    let text = match result {
+                        Ok(text) => text,
+                        Err(e) => {
+                            eprintln!("Transcription error: {}", e);
+                            report_error(&app, Some(job_id), Some(started_at), e.to_string());
+                            continue;
+                        }
+                    };
+
+                    // Text already typed progressively keeps the raw wording; the
+                    // corrected transcript still lands on the clipboard and history
+                    let typed = typed_chars > 0;
+                    let processed = post_process(&app, &text, &profile, job_app.as_deref());
+                    if processed.delete_previous && !typed && !job_edits_selection {
+                        delete_previous_insertion(&app);
+                    }
+                    let text = processed.text;
+                    println!("Transcription result: {}", text);
+
+                    // Nothing left after the commands, e.g. just "delete that"
+                    if text.trim().is_empty() {
+                        set_status(
+                            &app,
+                            Status::Success {
+                                job_id,
+                                elapsed_ms: elapsed_ms(started_at),
+                                audio_duration_ms,
+                                byte_size,
+                            },
+                        );
+                        std::thread::sleep(Duration::from_secs(2));
+                        set_status(&app, Status::Idle);
+                        continue;
+                    }
+
+                    *app.state::<AppState>().last_transcript.lock().unwrap() = Some(text.clone());
+
+                    // Record before delivering so a failed paste can be recovered
+                    let archive = app.state::<AudioArchive>().enabled();
+                    let recorded = record_history(
+                        &app,
+                        NewEntry {
+                            audio_duration_ms,
+                            latency_ms: elapsed_ms(stopped_at),
+                            model: config.model.clone(),
+                            profile_id: profile.id.clone(),
+                            app: job_app.clone(),
+                            text: text.clone(),
+                        },
+                        &wav_data,
+                        archive,
+                    );
+                    if let Err(e) = recorded {
+                        eprintln!("Failed to save history: {}", e);
+                    }
+
+                    // Text typed progressively is already in place, and clipboard-only
+                    // profiles never paste; either way leave the full transcript on
+                    // the clipboard
+                    let (delivered, inserted_chars) =
+                        if typed || profile.output == OutputSink::Clipboard {
+                            let copied = app
+                                .clipboard()
+                                .write_text(text.clone())
+                                .map_err(|e| format!("Clipboard error: {}", e));
+                            (copied, typed_chars)
+                        } else {
+                            (paste_text(&app, &text), text.chars().count())
+                        };
+                    if let Err(e) = delivered {
+                        eprintln!("{}", e);
+                        report_error(&app, Some(job_id), Some(started_at), e);
+                        continue;
+                    }
+                    *app.state::<AppState>().last_inserted_chars.lock().unwrap() = inserted_chars;
+
+                    set_status(
+                        &app,
+                        Status::Success {
+                            job_id,
+                            elapsed_ms: elapsed_ms(started_at),
+                            audio_duration_ms,
+                            byte_size,
+                        },
+                    );
+                    std::thread::sleep(Duration::from_secs(2));
+                    set_status(&app, Status::Idle);
                 }
                 None => break,
             }
@@ -713,13 +1352,28 @@ pub fn run() {
 
     tauri::Builder::default()
         .manage(AppState {
-            current_shortcut: Mutex::new(None),
+            shortcuts: Mutex::new(HashMap::new()),
+            profiles: Mutex::new(default_profiles()),
             audio_sender: Mutex::new(Some(tx)),
             api_key: Mutex::new(String::new()),
             model: Mutex::new(String::from("gemini-3-pro-preview")),
+            generation: Mutex::new(GenerationSettings::default()),
+            use_system_instruction: Mutex::new(true),
+            translation: Mutex::new(TranslationSettings::default()),
+            language: Mutex::new(Language::default()),
+            continuity: Mutex::new(ContinuitySettings::default()),
+            normalize: Mutex::new(NormalizeSettings::default()),
+            app_rules: Mutex::new(Vec::new()),
+            last_focused_app: Mutex::new(None),
+            streaming: Mutex::new(false),
+            type_progressively: Mutex::new(false),
             tray_icon: Mutex::new(None),
             animation_running: Arc::new(Mutex::new(false)),
             current_animation: Mutex::new(None),
+            last_transcript: Mutex::new(None),
+            last_inserted_chars: Mutex::new(0),
+            hands_free: Mutex::new(false),
+            cancel_requested: Mutex::new(false),
         })
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -736,33 +1390,35 @@ pub fn run() {
                 }
             }
 
+            // Load prompts from the config directory and watch for edits
+            let prompt_dir = app.path().app_config_dir()?.join("prompts");
+            app.manage(PromptStore::new(prompt_dir));
+            watch_prompts(app.handle().clone());
+            let vocabulary_path = app.path().app_config_dir()?.join("vocabulary.json");
+            app.manage(VocabularyStore::new(vocabulary_path));
+            let rules_path = app.path().app_config_dir()?.join("rules.json");
+            app.manage(RuleStore::new(rules_path));
+            let voice_commands_path = app.path().app_config_dir()?.join("voice_commands.json");
+            app.manage(VoiceCommandStore::new(voice_commands_path));
+
+            // Load transcription history
+            let history_path = app.path().app_data_dir()?.join("history.jsonl");
+            app.manage(HistoryStore::new(history_path));
+            let recordings_dir = app.path().app_data_dir()?.join("recordings");
+            app.manage(AudioArchive::new(recordings_dir));
+
             // Start audio processing thread
             start_audio_processing(app.handle().clone(), rx);
 
             // Create tray menu
-            let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&settings, &quit])?;
+            let menu = build_tray_menu(app.handle())?;
 
             // Build tray icon
             let tray = TrayIconBuilder::new()
                 .icon(app.default_window_icon().unwrap().clone())
                 .tooltip("AquaVoice - Ready")
                 .menu(&menu)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    "settings" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.center();
-                            let _ = window.set_decorations(true);
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    _ => {}
-                })
+                .on_menu_event(|app, event| handle_tray_menu(app, event.id.as_ref()))
                 .build(app)?;
 
             // Store tray icon in app state
@@ -791,7 +1447,39 @@ pub fn run() {
             execute_paste,
             set_api_key,
             set_model,
-            register_shortcut
+            set_generation_config,
+            set_use_system_instruction,
+            set_translation_settings,
+            set_language,
+            set_continuity_settings,
+            set_normalize_settings,
+            set_app_rules,
+            get_last_focused_app,
+            get_prompt,
+            set_prompt,
+            validate_prompt,
+            get_vocabulary,
+            set_vocabulary,
+            get_rules,
+            set_rules,
+            test_rules,
+            get_voice_commands,
+            set_voice_commands,
+            set_streaming,
+            get_profiles,
+            get_default_profiles,
+            set_profiles,
+            register_shortcut,
+            unregister_shortcut,
+            list_shortcuts,
+            list_history,
+            search_history,
+            delete_history,
+            set_history_retention,
+            set_archive_settings,
+            list_archived_recordings,
+            retranscribe,
+            set_push_to_talk
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
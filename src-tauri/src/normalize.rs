@@ -0,0 +1,267 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Width of Latin letters and digits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WidthStyle {
+    #[default]
+    Keep,
+    /// `ＡＢＣ１２３` → `ABC123`
+    Half,
+    /// `ABC123` → `ＡＢＣ１２３`
+    Full,
+}
+
+/// Which comma and full stop to use in Japanese text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PunctuationStyle {
+    #[default]
+    Keep,
+    /// `、` and `。`
+    Touten,
+    /// `，` and `．`
+    Comma,
+    /// `，` and `。`
+    CommaMaru,
+}
+
+/// Spaces between Japanese and Latin text, e.g. `Rustで` vs `Rust で`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpacingStyle {
+    #[default]
+    Keep,
+    Remove,
+    Insert,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizeSettings {
+    pub alphanumeric_width: WidthStyle,
+    pub punctuation: PunctuationStyle,
+    pub spacing: SpacingStyle,
+    /// Turn the ideographic space `　` into an ASCII space.
+    pub half_width_space: bool,
+}
+
+/// Offset between an ASCII character and its full-width form.
+const FULL_WIDTH_OFFSET: u32 = 0xFEE0;
+
+fn to_half_width(c: char) -> char {
+    match c {
+        '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => {
+            char::from_u32(c as u32 - FULL_WIDTH_OFFSET).unwrap_or(c)
+        }
+        _ => c,
+    }
+}
+
+fn to_full_width(c: char) -> char {
+    if c.is_ascii_alphanumeric() {
+        char::from_u32(c as u32 + FULL_WIDTH_OFFSET).unwrap_or(c)
+    } else {
+        c
+    }
+}
+
+fn punctuation(c: char, style: PunctuationStyle) -> char {
+    let (comma, stop) = match style {
+        PunctuationStyle::Keep => return c,
+        PunctuationStyle::Touten => ('、', '。'),
+        PunctuationStyle::Comma => ('，', '．'),
+        PunctuationStyle::CommaMaru => ('，', '。'),
+    };
+    match c {
+        '、' | '，' => comma,
+        '。' | '．' => stop,
+        _ => c,
+    }
+}
+
+const JAPANESE: &str = r"[\p{Han}\p{Hiragana}\p{Katakana}ー]";
+/// Start of an English word: a Latin letter in either width.
+const WORD_START: &str = r"[A-Za-zＡ-Ｚａ-ｚ]";
+/// End of an English word: a letter, or digits right after one as in
+/// `iPhone15`. Bare numbers are left alone, so `10月` and `2025年` keep
+/// their counters attached.
+const WORD_END: &str = r"[A-Za-zＡ-Ｚａ-ｚ][0-9０-９]*";
+
+struct SpacingPatterns {
+    japanese_latin: Regex,
+    latin_japanese: Regex,
+    japanese_space_latin: Regex,
+    latin_space_japanese: Regex,
+}
+
+fn spacing_patterns() -> &'static SpacingPatterns {
+    static PATTERNS: OnceLock<SpacingPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let regex = |pattern: String| Regex::new(&pattern).expect("valid spacing pattern");
+        SpacingPatterns {
+            japanese_latin: regex(format!("({})({})", JAPANESE, WORD_START)),
+            latin_japanese: regex(format!("({})({})", WORD_END, JAPANESE)),
+            japanese_space_latin: regex(format!("({})[ \t]+({})", JAPANESE, WORD_START)),
+            latin_space_japanese: regex(format!("({})[ \t]+({})", WORD_END, JAPANESE)),
+        }
+    })
+}
+
+/// Apply `settings` to `text`. Every option is a pure character or
+/// spacing rewrite, so the same input always gives the same output.
+pub fn normalize(text: &str, settings: &NormalizeSettings) -> String {
+    let mut text: String = text
+        .chars()
+        .map(|c| match settings.alphanumeric_width {
+            WidthStyle::Keep => c,
+            WidthStyle::Half => to_half_width(c),
+            WidthStyle::Full => to_full_width(c),
+        })
+        .map(|c| punctuation(c, settings.punctuation))
+        .map(|c| {
+            if settings.half_width_space && c == '　' {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    let patterns = spacing_patterns();
+    match settings.spacing {
+        SpacingStyle::Keep => {}
+        SpacingStyle::Remove => {
+            text = patterns
+                .japanese_space_latin
+                .replace_all(&text, "$1$2")
+                .into_owned();
+            text = patterns
+                .latin_space_japanese
+                .replace_all(&text, "$1$2")
+                .into_owned();
+        }
+        SpacingStyle::Insert => {
+            text = patterns
+                .japanese_latin
+                .replace_all(&text, "$1 $2")
+                .into_owned();
+            text = patterns
+                .latin_japanese
+                .replace_all(&text, "$1 $2")
+                .into_owned();
+        }
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_width(alphanumeric_width: WidthStyle) -> NormalizeSettings {
+        NormalizeSettings {
+            alphanumeric_width,
+            ..NormalizeSettings::default()
+        }
+    }
+
+    fn with_punctuation(punctuation: PunctuationStyle) -> NormalizeSettings {
+        NormalizeSettings {
+            punctuation,
+            ..NormalizeSettings::default()
+        }
+    }
+
+    fn with_spacing(spacing: SpacingStyle) -> NormalizeSettings {
+        NormalizeSettings {
+            spacing,
+            ..NormalizeSettings::default()
+        }
+    }
+
+    #[test]
+    fn default_settings_keep_text() {
+        let text = "ＡＢＣ１２３，Rust で書く。　";
+        assert_eq!(normalize(text, &NormalizeSettings::default()), text);
+    }
+
+    #[test]
+    fn width_conversion() {
+        let half = with_width(WidthStyle::Half);
+        assert_eq!(normalize("ＡＢＣ１２３とｘｙｚ", &half), "ABC123とxyz");
+        let full = with_width(WidthStyle::Full);
+        assert_eq!(normalize("ABC123とxyz", &full), "ＡＢＣ１２３とｘｙｚ");
+        // Only letters and digits change width
+        assert_eq!(normalize("a-b, c!", &full), "ａ-ｂ, ｃ!");
+    }
+
+    #[test]
+    fn punctuation_styles() {
+        let text = "はい、そうです。いいえ，違います．";
+        assert_eq!(
+            normalize(text, &with_punctuation(PunctuationStyle::Touten)),
+            "はい、そうです。いいえ、違います。"
+        );
+        assert_eq!(
+            normalize(text, &with_punctuation(PunctuationStyle::Comma)),
+            "はい，そうです．いいえ，違います．"
+        );
+        assert_eq!(
+            normalize(text, &with_punctuation(PunctuationStyle::CommaMaru)),
+            "はい，そうです。いいえ，違います。"
+        );
+        // ASCII punctuation in English text is untouched
+        assert_eq!(
+            normalize("Hi, there.", &with_punctuation(PunctuationStyle::Touten)),
+            "Hi, there."
+        );
+    }
+
+    #[test]
+    fn half_width_space() {
+        let settings = NormalizeSettings {
+            half_width_space: true,
+            ..NormalizeSettings::default()
+        };
+        assert_eq!(normalize("はい　そうです", &settings), "はい そうです");
+    }
+
+    #[test]
+    fn spacing_insert_around_english_words() {
+        let insert = with_spacing(SpacingStyle::Insert);
+        assert_eq!(
+            normalize("Rustでアプリを作る", &insert),
+            "Rust でアプリを作る"
+        );
+        assert_eq!(normalize("今日はTauriの話", &insert), "今日は Tauri の話");
+        assert_eq!(normalize("iPhone15を買った", &insert), "iPhone15 を買った");
+        assert_eq!(normalize("Ｒｕｓｔで", &insert), "Ｒｕｓｔ で");
+    }
+
+    #[test]
+    fn spacing_insert_leaves_numbers_and_counters() {
+        let insert = with_spacing(SpacingStyle::Insert);
+        assert_eq!(normalize("2025年10月3日", &insert), "2025年10月3日");
+        assert_eq!(normalize("３個と5人", &insert), "３個と5人");
+        assert_eq!(normalize("第2章", &insert), "第2章");
+    }
+
+    #[test]
+    fn spacing_remove() {
+        let remove = with_spacing(SpacingStyle::Remove);
+        assert_eq!(
+            normalize("Rust で書いた Tauri アプリ", &remove),
+            "Rustで書いたTauriアプリ"
+        );
+        assert_eq!(normalize("iPhone15 を買った", &remove), "iPhone15を買った");
+        // Spaces between English words stay
+        assert_eq!(
+            normalize("Hello world と言う", &remove),
+            "Hello worldと言う"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prompts::DEFAULT_PROMPT;
+
+/// Profile used by the main recording shortcut.
+pub const DEFAULT_PROFILE: &str = "default";
+/// Profile used when editing the selected text by voice.
+pub const EDIT_PROFILE: &str = "edit-selection";
+/// Profile that translates using the languages from the settings.
+pub const TRANSLATE_PROFILE: &str = "translate";
+
+/// Where a finished transcript goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputSink {
+    /// Copy to the clipboard and paste into the focused app.
+    #[default]
+    Paste,
+    /// Copy to the clipboard only.
+    Clipboard,
+}
+
+/// A named combination of prompt, model and output, selected by shortcut.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    /// Prompt file name in the prompt directory (without `.txt`).
+    pub prompt: String,
+    /// Overrides the global model when set.
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub output: OutputSink,
+    /// Translate between the languages in the translation settings.
+    #[serde(default)]
+    pub translate: bool,
+    /// Run spoken commands over the transcript. Only meaningful for plain
+    /// dictation; other profiles' output was never spoken verbatim. Unset
+    /// means the built-in behaviour: on for the default profile only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voice_commands: Option<bool>,
+}
+
+impl Profile {
+    fn new(id: &str, name: &str, prompt: &str, output: OutputSink) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+            model: None,
+            output,
+            translate: false,
+            voice_commands: None,
+        }
+    }
+
+    pub fn voice_commands(&self) -> bool {
+        self.voice_commands.unwrap_or(self.id == DEFAULT_PROFILE)
+    }
+}
+
+pub fn default_profiles() -> Vec<Profile> {
+    vec![
+        Profile::new(DEFAULT_PROFILE, "Japanese dev", DEFAULT_PROMPT, OutputSink::Paste),
+        Profile::new("english-email", "English email", "english-email", OutputSink::Paste),
+        Profile {
+            translate: true,
+            ..Profile::new(TRANSLATE_PROFILE, "Translate", "translate", OutputSink::Paste)
+        },
+        Profile::new("commit-message", "Commit message", "commit-message", OutputSink::Clipboard),
+        Profile::new(EDIT_PROFILE, "Edit selection", "edit-selection", OutputSink::Paste),
+    ]
+}
+
+/// The built-in profiles with `saved` ones (from settings.json) applied on
+/// top: a saved profile replaces the built-in one with the same ID, and
+/// built-in profiles added since the settings were saved still show up.
+pub fn merge_profiles(saved: Vec<Profile>) -> Vec<Profile> {
+    let mut profiles = default_profiles();
+    for profile in saved {
+        match profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(built_in) => *built_in = profile,
+            None => profiles.push(profile),
+        }
+    }
+    profiles
+}
+
+/// Look up `id`, falling back to the default profile (and then the first
+/// one) so a stale binding still produces a transcript.
+pub fn find_profile(profiles: &[Profile], id: &str) -> Profile {
+    profiles
+        .iter()
+        .find(|p| p.id == id)
+        .or_else(|| profiles.iter().find(|p| p.id == DEFAULT_PROFILE))
+        .or_else(|| profiles.first())
+        .cloned()
+        .unwrap_or_else(|| default_profiles().remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_profiles_override_built_in_ones_by_id() {
+        let saved = vec![
+            Profile {
+                model: Some("gemini-2.5-flash".to_string()),
+                ..Profile::new("english-email", "Email", "email", OutputSink::Clipboard)
+            },
+            Profile::new("notes", "Notes", "notes", OutputSink::Paste),
+        ];
+        let profiles = merge_profiles(saved);
+
+        let ids: Vec<&str> = profiles.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                DEFAULT_PROFILE,
+                "english-email",
+                TRANSLATE_PROFILE,
+                "commit-message",
+                EDIT_PROFILE,
+                "notes"
+            ]
+        );
+        let email = &profiles[1];
+        assert_eq!(email.name, "Email");
+        assert_eq!(email.output, OutputSink::Clipboard);
+        assert_eq!(email.model.as_deref(), Some("gemini-2.5-flash"));
+    }
+
+    #[test]
+    fn voice_commands_default_to_the_built_in_setting() {
+        let json = r#"[
+            { "id": "default", "name": "Dev", "prompt": "transcription" },
+            { "id": "notes", "name": "Notes", "prompt": "notes" },
+            { "id": "memo", "name": "Memo", "prompt": "notes", "voiceCommands": true }
+        ]"#;
+        let profiles: Vec<Profile> = serde_json::from_str(json).unwrap();
+        let enabled: Vec<bool> = profiles.iter().map(Profile::voice_commands).collect();
+        assert_eq!(enabled, [true, false, true]);
+        assert!(default_profiles()
+            .iter()
+            .all(|p| p.voice_commands() == (p.id == DEFAULT_PROFILE)));
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Name of the prompt used when nothing else is selected.
+pub const DEFAULT_PROMPT: &str = "transcription";
+
+/// Prompts shipped with the app; copied to the config directory on first
+/// run so they can be edited without rebuilding.
+const BUNDLED_PROMPTS: &[(&str, &str)] = &[
+    (DEFAULT_PROMPT, include_str!("../prompts/transcription.txt")),
+    ("english-email", include_str!("../prompts/english-email.txt")),
+    ("translate", include_str!("../prompts/translate.txt")),
+    ("commit-message", include_str!("../prompts/commit-message.txt")),
+    ("edit-selection", include_str!("../prompts/edit-selection.txt")),
+];
+
+struct LoadedPrompt {
+    text: String,
+    modified: Option<SystemTime>,
+}
+
+/// Prompt files in `<app config dir>/prompts/<name>.txt`, cached in memory
+/// and reloaded when the file changes on disk.
+pub struct PromptStore {
+    dir: PathBuf,
+    prompts: Mutex<HashMap<String, LoadedPrompt>>,
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid prompt name: {}", name));
+    }
+    Ok(())
+}
+
+fn bundled(name: &str) -> Option<&'static str> {
+    BUNDLED_PROMPTS
+        .iter()
+        .find(|(bundled_name, _)| *bundled_name == name)
+        .map(|(_, text)| *text)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl PromptStore {
+    pub fn new(dir: PathBuf) -> Self {
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("Failed to create prompt directory {}: {}", dir.display(), e);
+        }
+
+        // Seed bundled prompts so users have a file to edit
+        for (name, text) in BUNDLED_PROMPTS {
+            let path = dir.join(format!("{}.txt", name));
+            if !path.exists() {
+                if let Err(e) = fs::write(&path, text) {
+                    eprintln!("Failed to write default prompt {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        Self {
+            dir,
+            prompts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.txt", name))
+    }
+
+    /// Read `name` from disk, falling back to the bundled copy.
+    fn load(&self, name: &str) -> Result<LoadedPrompt, String> {
+        let path = self.path(name);
+        match fs::read_to_string(&path) {
+            Ok(text) => Ok(LoadedPrompt {
+                text,
+                modified: modified(&path),
+            }),
+            Err(e) => match bundled(name) {
+                Some(text) => Ok(LoadedPrompt {
+                    text: text.to_string(),
+                    modified: None,
+                }),
+                None => Err(format!("Failed to read prompt {}: {}", path.display(), e)),
+            },
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<String, String> {
+        validate_name(name)?;
+
+        let mut prompts = self.prompts.lock().unwrap();
+        if let Some(prompt) = prompts.get(name) {
+            return Ok(prompt.text.clone());
+        }
+
+        let prompt = self.load(name)?;
+        let text = prompt.text.clone();
+        prompts.insert(name.to_string(), prompt);
+        Ok(text)
+    }
+
+    pub fn set(&self, name: &str, text: &str) -> Result<(), String> {
+        validate_name(name)?;
+
+        let path = self.path(name);
+        fs::write(&path, text)
+            .map_err(|e| format!("Failed to write prompt {}: {}", path.display(), e))?;
+
+        self.prompts.lock().unwrap().insert(
+            name.to_string(),
+            LoadedPrompt {
+                text: text.to_string(),
+                modified: modified(&path),
+            },
+        );
+        Ok(())
+    }
+
+    /// Re-read cached prompts whose file changed since they were loaded and
+    /// return their names.
+    fn reload_changed(&self) -> Vec<String> {
+        let mut prompts = self.prompts.lock().unwrap();
+        let mut changed = Vec::new();
+
+        for (name, prompt) in prompts.iter_mut() {
+            let current = modified(&self.path(name));
+            if current.is_none() || current == prompt.modified {
+                continue;
+            }
+            match self.load(name) {
+                Ok(reloaded) => {
+                    *prompt = reloaded;
+                    changed.push(name.clone());
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        changed
+    }
+}
+
+/// Poll the prompt directory and emit `prompt-changed` with the prompt name
+/// whenever a file is edited outside the app.
+pub fn watch_prompts(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(1));
+
+        let changed = app.state::<PromptStore>().reload_changed();
+        for name in changed {
+            println!("Reloaded prompt: {}", name);
+            let _ = app.emit("prompt-changed", name);
+        }
+    });
+}
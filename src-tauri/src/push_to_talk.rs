@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::profiles::DEFAULT_PROFILE;
+
+/// Settings for the low-level push-to-talk listener, which can trigger on a
+/// key the global shortcut plugin cannot register on its own (a bare
+/// modifier such as Right Alt, or a single key without modifiers).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushToTalkConfig {
+    pub enabled: bool,
+    /// One of the names in `TRIGGER_KEYS`.
+    pub key: String,
+    pub profile_id: String,
+    /// Ignore a press this soon after the previous release (key chatter).
+    pub debounce_ms: u64,
+    /// Releases before this are treated as a tap and discarded.
+    pub min_hold_ms: u64,
+}
+
+impl Default for PushToTalkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key: "RightAlt".to_string(),
+            profile_id: DEFAULT_PROFILE.to_string(),
+            debounce_ms: 50,
+            min_hold_ms: 200,
+        }
+    }
+}
+
+/// Trigger key names and their Linux input event codes
+/// (`linux/input-event-codes.h`).
+const TRIGGER_KEYS: &[(&str, u16)] = &[
+    ("LeftControl", 29),
+    ("LeftShift", 42),
+    ("LeftAlt", 56),
+    ("RightShift", 54),
+    ("RightControl", 97),
+    ("RightAlt", 100),
+    ("LeftSuper", 125),
+    ("RightSuper", 126),
+    ("Fn", 464),
+    ("CapsLock", 58),
+    ("ScrollLock", 70),
+    ("Pause", 119),
+    ("F13", 183),
+    ("F14", 184),
+    ("F15", 185),
+    ("F16", 186),
+    ("F17", 187),
+    ("F18", 188),
+    ("F19", 189),
+    ("F20", 190),
+    ("F21", 191),
+    ("F22", 192),
+    ("F23", 193),
+    ("F24", 194),
+];
+
+fn trigger_code(name: &str) -> Result<u16, String> {
+    TRIGGER_KEYS
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+        .ok_or_else(|| format!("Unsupported push-to-talk key: {}", name))
+}
+
+/// Bumped on every reconfiguration; listener threads exit once their
+/// generation is stale.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Configuration the running listener was started with, if any.
+static ACTIVE: Mutex<Option<PushToTalkConfig>> = Mutex::new(None);
+
+/// Stop any running listener and start a new one for `config`. Settings are
+/// saved as a whole, so an unchanged `config` leaves the listener running.
+pub fn configure(app: &AppHandle, config: PushToTalkConfig) -> Result<(), String> {
+    let mut active = ACTIVE.lock().unwrap();
+    if active.as_ref() == Some(&config) {
+        return Ok(());
+    }
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *active = None;
+    if !config.enabled {
+        println!("Push-to-talk listener disabled");
+        *active = Some(config);
+        return Ok(());
+    }
+
+    let code = trigger_code(&config.key)?;
+    platform::start(app.clone(), config.clone(), code, generation)?;
+    *active = Some(config);
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{PushToTalkConfig, GENERATION};
+    use crate::{AppState, AudioCommand};
+    use std::fs::{self, File};
+    use std::io::{self, Read};
+    use std::os::fd::AsRawFd;
+    use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use tauri::{AppHandle, Manager};
+
+    const EV_KEY: u16 = 0x01;
+    const KEY_RELEASED: i32 = 0;
+    const KEY_PRESSED: i32 = 1;
+
+    /// `struct input_event`: a `timeval` followed by type, code and value.
+    const TIMEVAL_SIZE: usize = 2 * std::mem::size_of::<std::ffi::c_long>();
+    const EVENT_SIZE: usize = TIMEVAL_SIZE + 8;
+
+    /// How often a listener on a quiet device checks whether it is stale.
+    const POLL_INTERVAL_MS: i32 = 500;
+
+    fn is_current(generation: u64) -> bool {
+        GENERATION.load(Ordering::SeqCst) == generation
+    }
+
+    #[derive(Default)]
+    struct Hold {
+        pressed_at: Option<Instant>,
+        released_at: Option<Instant>,
+        /// Another key went down while the trigger was held, so the trigger
+        /// is being used as a modifier rather than for dictation.
+        chorded: bool,
+    }
+
+    pub fn start(
+        app: AppHandle,
+        config: PushToTalkConfig,
+        code: u16,
+        generation: u64,
+    ) -> Result<(), String> {
+        let entries =
+            fs::read_dir("/dev/input").map_err(|e| format!("Cannot read /dev/input: {}", e))?;
+
+        let devices: Vec<(String, File)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("event"))
+            })
+            .filter_map(|path| {
+                File::open(&path)
+                    .ok()
+                    .map(|file| (path.display().to_string(), file))
+            })
+            .collect();
+
+        if devices.is_empty() {
+            return Err(
+                "No readable input devices; add your user to the 'input' group to use push-to-talk"
+                    .to_string(),
+            );
+        }
+
+        println!(
+            "Push-to-talk listening for {} on {} devices",
+            config.key,
+            devices.len()
+        );
+
+        let hold = Arc::new(Mutex::new(Hold::default()));
+        let config = Arc::new(config);
+        for (path, file) in devices {
+            let app = app.clone();
+            let hold = Arc::clone(&hold);
+            let config = Arc::clone(&config);
+            std::thread::spawn(move || {
+                read_device(&app, file, code, &config, &hold, generation);
+                println!("Push-to-talk listener stopped: {}", path);
+            });
+        }
+        Ok(())
+    }
+
+    fn read_device(
+        app: &AppHandle,
+        mut file: File,
+        code: u16,
+        config: &PushToTalkConfig,
+        hold: &Mutex<Hold>,
+        generation: u64,
+    ) {
+        let mut buffer = [0u8; EVENT_SIZE];
+        let mut poll_fd = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        while is_current(generation) {
+            // Wait with a timeout rather than blocking in read, so the thread
+            // exits after a reconfiguration even if the device stays quiet
+            let ready = unsafe { libc::poll(&mut poll_fd, 1, POLL_INTERVAL_MS) };
+            if ready < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+            if ready == 0 {
+                continue;
+            }
+            if file.read_exact(&mut buffer).is_err() {
+                return;
+            }
+            // Configuration may have changed while waiting
+            if !is_current(generation) {
+                return;
+            }
+
+            let event_type = u16::from_ne_bytes([buffer[TIMEVAL_SIZE], buffer[TIMEVAL_SIZE + 1]]);
+            let event_code =
+                u16::from_ne_bytes([buffer[TIMEVAL_SIZE + 2], buffer[TIMEVAL_SIZE + 3]]);
+            let value = i32::from_ne_bytes([
+                buffer[TIMEVAL_SIZE + 4],
+                buffer[TIMEVAL_SIZE + 5],
+                buffer[TIMEVAL_SIZE + 6],
+                buffer[TIMEVAL_SIZE + 7],
+            ]);
+
+            // Auto-repeat (value 2) and non-key events are irrelevant
+            if event_type != EV_KEY || (value != KEY_PRESSED && value != KEY_RELEASED) {
+                continue;
+            }
+
+            let command = {
+                let mut hold = hold.lock().unwrap();
+                next_command(&mut hold, config, event_code == code, value == KEY_PRESSED)
+            };
+            if let Some(command) = command {
+                let sender = app.state::<AppState>().audio_sender.lock().unwrap().clone();
+                if let Some(tx) = sender {
+                    let _ = tx.blocking_send(command);
+                }
+            }
+        }
+    }
+
+    fn next_command(
+        hold: &mut Hold,
+        config: &PushToTalkConfig,
+        is_trigger: bool,
+        pressed: bool,
+    ) -> Option<AudioCommand> {
+        let now = Instant::now();
+
+        if !is_trigger {
+            // A chord like RightAlt+Tab: give the key back to its usual role
+            if pressed && hold.pressed_at.is_some() && !hold.chorded {
+                println!("Push-to-talk key used in a chord - cancelling");
+                hold.chorded = true;
+                return Some(AudioCommand::Cancel);
+            }
+            return None;
+        }
+
+        if pressed {
+            if hold.pressed_at.is_some() {
+                return None;
+            }
+            let debounce = Duration::from_millis(config.debounce_ms);
+            if hold
+                .released_at
+                .is_some_and(|released| now.duration_since(released) < debounce)
+            {
+                return None;
+            }
+            hold.pressed_at = Some(now);
+            hold.chorded = false;
+            println!("Push-to-talk pressed - starting recording");
+            return Some(AudioCommand::StartRecording {
+                profile_id: config.profile_id.clone(),
+                edit_selection: false,
+            });
+        }
+
+        let pressed_at = hold.pressed_at.take()?;
+        hold.released_at = Some(now);
+        if hold.chorded {
+            return None;
+        }
+        if now.duration_since(pressed_at) < Duration::from_millis(config.min_hold_ms) {
+            println!("Push-to-talk tapped - discarding");
+            return Some(AudioCommand::Cancel);
+        }
+        println!("Push-to-talk released - stopping recording");
+        Some(AudioCommand::StopRecording)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::PushToTalkConfig;
+    use tauri::AppHandle;
+
+    pub fn start(
+        _app: AppHandle,
+        _config: PushToTalkConfig,
+        _code: u16,
+        _generation: u64,
+    ) -> Result<(), String> {
+        Err("Modifier-only push-to-talk is only supported on Linux".to_string())
+    }
+}
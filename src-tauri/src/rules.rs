@@ -0,0 +1,340 @@
+use regex::{Captures, NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::json_file;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleKind {
+    /// Replace the exact text.
+    #[default]
+    Literal,
+    /// `pattern` is a regular expression; `$1` etc. work in the replacement.
+    Regex,
+}
+
+/// A find-and-replace applied to every transcript, e.g. `リアクト` → `React`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementRule {
+    #[serde(default)]
+    pub kind: RuleKind,
+    pub pattern: String,
+    pub replacement: String,
+    /// Match regardless of case, so `github` and `Github` both become `GitHub`.
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Only match at word boundaries, so `go` does not touch `google`. Only
+    /// ASCII letters, digits and `_` form words; Japanese has no spaces
+    /// between words, so `リアクト` still matches inside `リアクトで`.
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Profile IDs the rule applies to; empty means every profile.
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    /// Focused app identifiers the rule applies to; empty means every app.
+    #[serde(default)]
+    pub apps: Vec<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `text[start..end]` is not part of a longer ASCII word. An end
+/// of the match that is not itself an ASCII word character needs no
+/// boundary, since `\b` never holds between kana and kanji.
+fn at_word_boundaries(text: &str, start: usize, end: usize) -> bool {
+    let matched = &text[start..end];
+    let left =
+        !is_word_char(matched.chars().next()) || !is_word_char(text[..start].chars().next_back());
+    let right =
+        !is_word_char(matched.chars().next_back()) || !is_word_char(text[end..].chars().next());
+    left && right
+}
+
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    /// Literal replacements must not expand `$` references.
+    expand: bool,
+    whole_word: bool,
+    profiles: Vec<String>,
+    apps: Vec<String>,
+}
+
+/// Rules compiled once and applied in order.
+#[derive(Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    pub fn compile(rules: &[ReplacementRule]) -> Result<Self, String> {
+        let mut compiled = Vec::new();
+        for (index, rule) in rules.iter().enumerate() {
+            if !rule.enabled {
+                continue;
+            }
+            if rule.pattern.is_empty() {
+                return Err(format!("Rule {}: pattern is empty", index + 1));
+            }
+
+            let pattern = match rule.kind {
+                RuleKind::Literal => regex::escape(&rule.pattern),
+                RuleKind::Regex => rule.pattern.clone(),
+            };
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(rule.case_insensitive)
+                .build()
+                .map_err(|e| format!("Rule {}: {}", index + 1, e))?;
+
+            compiled.push(CompiledRule {
+                regex,
+                replacement: rule.replacement.clone(),
+                expand: rule.kind == RuleKind::Regex,
+                whole_word: rule.whole_word,
+                profiles: rule.profiles.clone(),
+                apps: rule.apps.clone(),
+            });
+        }
+        Ok(Self { rules: compiled })
+    }
+
+    /// `app` is the focused application, when known; rules limited to
+    /// particular apps are skipped without one.
+    pub fn apply(&self, text: &str, profile_id: &str, app: Option<&str>) -> String {
+        let mut text = text.to_string();
+        for rule in &self.rules {
+            if !rule.profiles.is_empty() && !rule.profiles.iter().any(|id| id == profile_id) {
+                continue;
+            }
+            if !rule.apps.is_empty()
+                && !app.is_some_and(|app| rule.apps.iter().any(|id| id.eq_ignore_ascii_case(app)))
+            {
+                continue;
+            }
+            let replaced = if rule.whole_word {
+                rule.regex.replace_all(&text, |caps: &Captures| {
+                    let matched = caps.get(0).expect("whole match");
+                    if !at_word_boundaries(&text, matched.start(), matched.end()) {
+                        return matched.as_str().to_string();
+                    }
+                    if rule.expand {
+                        let mut expanded = String::new();
+                        caps.expand(&rule.replacement, &mut expanded);
+                        expanded
+                    } else {
+                        rule.replacement.clone()
+                    }
+                })
+            } else if rule.expand {
+                rule.regex.replace_all(&text, rule.replacement.as_str())
+            } else {
+                rule.regex.replace_all(&text, NoExpand(&rule.replacement))
+            };
+            text = replaced.into_owned();
+        }
+        text
+    }
+}
+
+/// Replacement rules in `<app config dir>/rules.json`.
+pub struct RuleStore {
+    path: PathBuf,
+    rules: Mutex<Vec<ReplacementRule>>,
+    compiled: Mutex<RuleSet>,
+}
+
+impl RuleStore {
+    pub fn new(path: PathBuf) -> Self {
+        let rules: Vec<ReplacementRule> = json_file::load(&path);
+        let compiled = RuleSet::compile(&rules).unwrap_or_else(|e| {
+            eprintln!("Ignoring replacement rules: {}", e);
+            RuleSet::default()
+        });
+
+        Self {
+            path,
+            rules: Mutex::new(rules),
+            compiled: Mutex::new(compiled),
+        }
+    }
+
+    pub fn get(&self) -> Vec<ReplacementRule> {
+        self.rules.lock().unwrap().clone()
+    }
+
+    /// Validate and save `rules`; invalid rules are rejected as a whole.
+    pub fn set(&self, rules: Vec<ReplacementRule>) -> Result<(), String> {
+        let compiled = RuleSet::compile(&rules)?;
+
+        json_file::save(&self.path, &rules)?;
+
+        *self.rules.lock().unwrap() = rules;
+        *self.compiled.lock().unwrap() = compiled;
+        Ok(())
+    }
+
+    pub fn apply(&self, text: &str, profile_id: &str, app: Option<&str>) -> String {
+        self.compiled.lock().unwrap().apply(text, profile_id, app)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: RuleKind, pattern: &str, replacement: &str) -> ReplacementRule {
+        ReplacementRule {
+            kind,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            case_insensitive: false,
+            whole_word: false,
+            profiles: Vec::new(),
+            apps: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    fn literal(pattern: &str, replacement: &str) -> ReplacementRule {
+        rule(RuleKind::Literal, pattern, replacement)
+    }
+
+    fn apply(rules: &[ReplacementRule], text: &str) -> String {
+        RuleSet::compile(rules)
+            .unwrap()
+            .apply(text, "default", None)
+    }
+
+    #[test]
+    fn literal_rules_match_exact_text() {
+        assert_eq!(
+            apply(&[literal("リアクト", "React")], "リアクトで書く"),
+            "Reactで書く"
+        );
+        assert_eq!(apply(&[literal("a.c", "x")], "abc a.c"), "abc x");
+    }
+
+    #[test]
+    fn regex_rules_expand_groups() {
+        let rules = [rule(RuleKind::Regex, r"(\d+)パー", "$1%")];
+        assert_eq!(apply(&rules, "5パーと10パー"), "5%と10%");
+    }
+
+    #[test]
+    fn literal_rules_do_not_expand_dollars() {
+        assert_eq!(apply(&[literal("ドル", "$1")], "1ドル"), "1$1");
+        let rules = [ReplacementRule {
+            whole_word: true,
+            ..literal("price", "$1")
+        }];
+        assert_eq!(apply(&rules, "the price"), "the $1");
+    }
+
+    #[test]
+    fn case_insensitive_matching() {
+        let rules = [ReplacementRule {
+            case_insensitive: true,
+            ..literal("github", "GitHub")
+        }];
+        assert_eq!(apply(&rules, "Github and GITHUB"), "GitHub and GitHub");
+        assert_eq!(apply(&[literal("github", "GitHub")], "Github"), "Github");
+    }
+
+    #[test]
+    fn whole_word_skips_longer_ascii_words() {
+        let rules = [ReplacementRule {
+            whole_word: true,
+            ..literal("go", "Go")
+        }];
+        assert_eq!(
+            apply(&rules, "go to google, go_lang, go"),
+            "Go to google, go_lang, Go"
+        );
+        assert_eq!(apply(&rules, "goで書いたgo"), "Goで書いたGo");
+    }
+
+    #[test]
+    fn whole_word_matches_japanese_patterns() {
+        let rules = [ReplacementRule {
+            whole_word: true,
+            ..literal("リアクト", "React")
+        }];
+        assert_eq!(apply(&rules, "リアクトとGo"), "ReactとGo");
+        assert_eq!(apply(&rules, "このリアクトで"), "このReactで");
+    }
+
+    #[test]
+    fn whole_word_regex_rules_expand_groups() {
+        let rules = [ReplacementRule {
+            whole_word: true,
+            ..rule(RuleKind::Regex, r"v(\d+)", "version $1")
+        }];
+        assert_eq!(apply(&rules, "v2 and dev2"), "version 2 and dev2");
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        let rules = [literal("リアクト", "React"), literal("React", "React.js")];
+        assert_eq!(apply(&rules, "リアクト"), "React.js");
+    }
+
+    #[test]
+    fn profile_scoping() {
+        let rules = [ReplacementRule {
+            profiles: vec!["english-email".to_string()],
+            ..literal("thx", "thanks")
+        }];
+        let set = RuleSet::compile(&rules).unwrap();
+        assert_eq!(set.apply("thx", "english-email", None), "thanks");
+        assert_eq!(set.apply("thx", "default", None), "thx");
+    }
+
+    #[test]
+    fn app_scoping() {
+        let rules = [ReplacementRule {
+            apps: vec!["com.apple.Terminal".to_string()],
+            ..literal("エルエス", "ls")
+        }];
+        let set = RuleSet::compile(&rules).unwrap();
+        assert_eq!(
+            set.apply("エルエス", "default", Some("com.apple.terminal")),
+            "ls"
+        );
+        assert_eq!(
+            set.apply("エルエス", "default", Some("com.google.Chrome")),
+            "エルエス"
+        );
+        assert_eq!(set.apply("エルエス", "default", None), "エルエス");
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let rules = [ReplacementRule {
+            enabled: false,
+            ..literal("a", "b")
+        }];
+        assert_eq!(apply(&rules, "a"), "a");
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let error = RuleSet::compile(&[literal("", "x")]).err().unwrap();
+        assert_eq!(error, "Rule 1: pattern is empty");
+        let rules = [literal("ok", "x"), rule(RuleKind::Regex, "(", "x")];
+        assert!(RuleSet::compile(&rules)
+            .err()
+            .unwrap()
+            .starts_with("Rule 2:"));
+    }
+}
@@ -0,0 +1,83 @@
+use tauri::AppHandle;
+
+/// Text currently selected in the focused app, if any.
+///
+/// On macOS this simulates Command+C and restores the previous clipboard
+/// afterwards: text and images are put back, anything else stays untouched
+/// unless the copy replaced it. On Linux it reads the primary selection,
+/// which needs `wl-paste` (Wayland) or `xclip` (X11).
+#[cfg(target_os = "macos")]
+pub fn selected_text(app: &AppHandle) -> Option<String> {
+    use std::process::Command;
+    use std::time::Duration;
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    // A marker tells "nothing selected" apart from "selection equals clipboard"
+    const MARKER: &str = "\u{0}aqua-voice-no-selection\u{0}";
+
+    let previous = app.clipboard().read_text().ok();
+    // Without text on the clipboard any text after the copy is the
+    // selection, so an image or files need no marker written over them
+    let previous_image = match &previous {
+        Some(_) => {
+            app.clipboard().write_text(MARKER.to_string()).ok()?;
+            None
+        }
+        None => app
+            .clipboard()
+            .read_image()
+            .ok()
+            .map(|image| image.to_owned()),
+    };
+
+    let script = r#"
+        tell application "System Events"
+            keystroke "c" using command down
+        end tell
+    "#;
+    if let Err(e) = Command::new("osascript").arg("-e").arg(script).output() {
+        println!("Failed to execute copy command: {}", e);
+    }
+    std::thread::sleep(Duration::from_millis(150));
+
+    let copied = app.clipboard().read_text().ok();
+    if let Some(previous) = previous {
+        let _ = app.clipboard().write_text(previous);
+    } else if let (Some(_), Some(image)) = (&copied, previous_image) {
+        let _ = app.clipboard().write_image(&image);
+    }
+
+    copied.filter(|text| text != MARKER && !text.is_empty())
+}
+
+#[cfg(target_os = "linux")]
+pub fn selected_text(_app: &AppHandle) -> Option<String> {
+    use std::process::Command;
+
+    let output = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Command::new("wl-paste")
+            .args(["--primary", "--no-newline"])
+            .output()
+    } else {
+        Command::new("xclip")
+            .args(["-o", "-selection", "primary"])
+            .output()
+    };
+
+    match output {
+        Ok(o) if o.status.success() => {
+            let text = String::from_utf8_lossy(&o.stdout).to_string();
+            (!text.is_empty()).then_some(text)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            println!("Failed to read primary selection: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn selected_text(_app: &AppHandle) -> Option<String> {
+    None
+}
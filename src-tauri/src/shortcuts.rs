@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::accelerator::{parse_shortcut, Accelerator};
+use crate::profiles::{DEFAULT_PROFILE, EDIT_PROFILE};
+use crate::{paste_last_transcript, show_history, AppState, AudioCommand};
+
+/// What a registered shortcut does.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ShortcutAction {
+    /// Push-to-talk: record while held, transcribe with the profile on release.
+    Dictate { profile_id: String },
+    /// Push-to-talk: speak an instruction and replace the selected text with
+    /// the rewritten version.
+    EditSelection { profile_id: String },
+    /// Discard the current recording or in-flight transcription.
+    Cancel,
+    /// Paste the most recent transcript again.
+    RepasteLast,
+    /// Show the settings window on the history view.
+    OpenHistory,
+    /// Press once to start recording, again to stop and transcribe.
+    ToggleHandsFree { profile_id: String },
+}
+
+impl Default for ShortcutAction {
+    fn default() -> Self {
+        ShortcutAction::Dictate {
+            profile_id: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+/// A registered shortcut, keyed by binding ID in `AppState::shortcuts`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub id: String,
+    /// Canonical form of the registered shortcut.
+    pub shortcut: String,
+    pub action: ShortcutAction,
+    #[serde(skip)]
+    registered: Shortcut,
+}
+
+/// Why a shortcut could not be registered, serialized as
+/// `{ "kind": "conflict", "shortcut": "Control+Space", "bindingId": "cancel" }`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ShortcutError {
+    /// The string is not a valid shortcut.
+    Invalid { message: String },
+    /// Another binding in this app already uses the shortcut.
+    Conflict { shortcut: String, binding_id: String },
+    /// The OS refused the shortcut, usually because another application holds it.
+    Unavailable { shortcut: String, message: String },
+    /// Unregistering the shortcut failed.
+    Failed { message: String },
+}
+
+fn send(app: &AppHandle, command: AudioCommand) {
+    let sender = app.state::<AppState>().audio_sender.lock().unwrap().clone();
+    if let Some(tx) = sender {
+        let _ = tx.blocking_send(command);
+    }
+}
+
+/// Dispatch to whichever binding currently owns `shortcut`, so a binding's
+/// action can change without re-registering the OS hotkey.
+fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutState) {
+    let state = app.state::<AppState>();
+    let action = state
+        .shortcuts
+        .lock()
+        .unwrap()
+        .values()
+        .find(|binding| binding.registered == *shortcut)
+        .map(|binding| binding.action.clone());
+    let Some(action) = action else {
+        return;
+    };
+
+    match (&action, event) {
+        (ShortcutAction::Dictate { profile_id }, ShortcutState::Pressed) => {
+            println!("Shortcut pressed - starting recording ({})", profile_id);
+            send(
+                app,
+                AudioCommand::StartRecording {
+                    profile_id: profile_id.clone(),
+                    // The edit prompt is useless without the selection
+                    edit_selection: profile_id == EDIT_PROFILE,
+                },
+            );
+        }
+        (ShortcutAction::EditSelection { profile_id }, ShortcutState::Pressed) => {
+            println!("Edit shortcut pressed - starting recording ({})", profile_id);
+            send(
+                app,
+                AudioCommand::StartRecording {
+                    profile_id: profile_id.clone(),
+                    edit_selection: true,
+                },
+            );
+        }
+        (
+            ShortcutAction::Dictate { .. } | ShortcutAction::EditSelection { .. },
+            ShortcutState::Released,
+        ) => {
+            println!("Shortcut released - stopping recording");
+            send(app, AudioCommand::StopRecording);
+        }
+        // Every other action fires on press only
+        (_, ShortcutState::Released) => {}
+        (ShortcutAction::Cancel, ShortcutState::Pressed) => {
+            println!("Cancel shortcut pressed");
+            *state.hands_free.lock().unwrap() = false;
+            *state.cancel_requested.lock().unwrap() = true;
+            send(app, AudioCommand::Cancel);
+        }
+        (ShortcutAction::RepasteLast, ShortcutState::Pressed) => {
+            paste_last_transcript(app);
+        }
+        (ShortcutAction::OpenHistory, ShortcutState::Pressed) => {
+            show_history(app);
+        }
+        (ShortcutAction::ToggleHandsFree { profile_id }, ShortcutState::Pressed) => {
+            let start = {
+                let mut hands_free = state.hands_free.lock().unwrap();
+                *hands_free = !*hands_free;
+                *hands_free
+            };
+            if start {
+                println!("Hands-free on - starting recording ({})", profile_id);
+                send(
+                    app,
+                    AudioCommand::StartRecording {
+                        profile_id: profile_id.clone(),
+                        edit_selection: false,
+                    },
+                );
+            } else {
+                println!("Hands-free off - stopping recording");
+                send(app, AudioCommand::StopRecording);
+            }
+        }
+    }
+}
+
+/// Bind `shortcut_str` to `action` under `id`, replacing whatever was
+/// bound to that ID before. Bindings under other IDs are left alone.
+///
+/// The previous shortcut for `id` stays registered until the new one has
+/// been accepted by the OS, so a failed change never leaves `id` unbound.
+pub fn register(
+    app: &AppHandle,
+    id: &str,
+    shortcut_str: &str,
+    action: ShortcutAction,
+) -> Result<(), ShortcutError> {
+    let state = app.state::<AppState>();
+
+    let shortcut =
+        parse_shortcut(shortcut_str).map_err(|message| ShortcutError::Invalid { message })?;
+    let canonical = Accelerator(shortcut).to_string();
+
+    // Look up the current owner without holding the lock across plugin calls;
+    // the shortcut handler takes the same lock on the event loop thread
+    let (previous, owner) = {
+        let shortcuts = state.shortcuts.lock().unwrap();
+        let previous = shortcuts.get(id).map(|binding| binding.registered);
+        let owner = shortcuts
+            .values()
+            .find(|binding| binding.id != id && binding.registered == shortcut)
+            .map(|binding| binding.id.clone());
+        (previous, owner)
+    };
+
+    if let Some(binding_id) = owner {
+        return Err(ShortcutError::Conflict {
+            shortcut: canonical,
+            binding_id,
+        });
+    }
+
+    // Same keys as before: only the action changes
+    if previous != Some(shortcut) {
+        app.global_shortcut()
+            .on_shortcut(shortcut, |app, shortcut, event| {
+                handle_shortcut(app, shortcut, event.state)
+            })
+            .map_err(|e| ShortcutError::Unavailable {
+                shortcut: canonical.clone(),
+                message: e.to_string(),
+            })?;
+
+        if let Some(old) = previous {
+            if let Err(e) = app.global_shortcut().unregister(old) {
+                eprintln!("Failed to unregister previous shortcut for {}: {}", id, e);
+            }
+        }
+    }
+
+    state.shortcuts.lock().unwrap().insert(
+        id.to_string(),
+        ShortcutBinding {
+            id: id.to_string(),
+            shortcut: canonical,
+            action,
+            registered: shortcut,
+        },
+    );
+
+    println!("Registered shortcut {}: {}", id, shortcut_str);
+    Ok(())
+}
+
+pub fn unregister(app: &AppHandle, id: &str) -> Result<(), ShortcutError> {
+    let state = app.state::<AppState>();
+    let removed = state.shortcuts.lock().unwrap().remove(id);
+    if let Some(binding) = removed {
+        app.global_shortcut()
+            .unregister(binding.registered)
+            .map_err(|e| ShortcutError::Failed {
+                message: e.to_string(),
+            })?;
+        println!("Unregistered shortcut {}", id);
+    }
+    Ok(())
+}
+
+pub fn list(app: &AppHandle) -> Vec<ShortcutBinding> {
+    let state = app.state::<AppState>();
+    let mut bindings: Vec<ShortcutBinding> =
+        state.shortcuts.lock().unwrap().values().cloned().collect();
+    bindings.sort_by(|a, b| a.id.cmp(&b.id));
+    bindings
+}
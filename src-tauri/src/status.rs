@@ -0,0 +1,75 @@
+use serde::Serialize;
+
+/// Pipeline status shared by the tray icon and the settings window.
+///
+/// Serialized as `{ "kind": "recording", "jobId": 3, ... }` on the
+/// `status-changed` event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum Status {
+    Idle,
+    Recording {
+        job_id: u64,
+    },
+    Processing {
+        job_id: u64,
+        elapsed_ms: u64,
+        audio_duration_ms: u64,
+    },
+    Transcribing {
+        job_id: u64,
+        elapsed_ms: u64,
+        audio_duration_ms: u64,
+        byte_size: usize,
+    },
+    Success {
+        job_id: u64,
+        elapsed_ms: u64,
+        audio_duration_ms: u64,
+        byte_size: usize,
+    },
+    Error {
+        job_id: Option<u64>,
+        elapsed_ms: Option<u64>,
+        message: String,
+    },
+}
+
+/// Tray icon animations, one per in-progress status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Animation {
+    Recording,
+    Processing,
+    Transcribing,
+}
+
+impl Status {
+    pub fn animation(&self) -> Option<Animation> {
+        match self {
+            Status::Recording { .. } => Some(Animation::Recording),
+            Status::Processing { .. } => Some(Animation::Processing),
+            Status::Transcribing { .. } => Some(Animation::Transcribing),
+            Status::Idle | Status::Success { .. } | Status::Error { .. } => None,
+        }
+    }
+
+    pub fn tooltip(&self) -> &'static str {
+        match self {
+            Status::Idle => "AquaVoice - Ready",
+            Status::Recording { .. } => "AquaVoice - Recording...",
+            Status::Processing { .. } => "AquaVoice - Processing...",
+            Status::Transcribing { .. } => "AquaVoice - Transcribing...",
+            Status::Success { .. } => "AquaVoice - Done",
+            Status::Error { .. } => "AquaVoice - Error",
+        }
+    }
+}
+
+/// Payload of `transcript-partial`, emitted while a streamed response arrives.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialTranscript {
+    pub job_id: u64,
+    pub delta: String,
+    pub text: String,
+}
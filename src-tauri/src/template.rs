@@ -0,0 +1,104 @@
+/// Variables a prompt can reference as `{{name}}`.
+pub const VARIABLES: &[&str] = &[
+    "language",
+    "vocabulary",
+    "app_name",
+    "date",
+    "selection",
+    "previous_transcript",
+];
+
+/// Values for one request. Empty strings render as nothing.
+#[derive(Debug, Default)]
+pub struct TemplateContext {
+    /// Instruction for the spoken language.
+    pub language: String,
+    /// Glossary section built from the user dictionary.
+    pub vocabulary: String,
+    /// Bundle ID or window class of the focused app.
+    pub app_name: String,
+    /// Local date as `YYYY-MM-DD`.
+    pub date: String,
+    /// Text selected when editing by voice.
+    pub selection: String,
+    pub previous_transcript: String,
+}
+
+impl TemplateContext {
+    fn get(&self, name: &str) -> Option<&str> {
+        let value = match name {
+            "language" => &self.language,
+            "vocabulary" => &self.vocabulary,
+            "app_name" => &self.app_name,
+            "date" => &self.date,
+            "selection" => &self.selection,
+            "previous_transcript" => &self.previous_transcript,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+fn line_of(template: &str, offset: usize) -> usize {
+    template[..offset].matches('\n').count() + 1
+}
+
+/// Split `template` into text and `{{variable}}` references, rejecting
+/// unclosed braces and names not in [`VARIABLES`].
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let offset = template.len() - rest.len() + start;
+        segments.push(Segment::Text(&rest[..start]));
+
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("Unclosed {{{{ on line {}", line_of(template, offset)))?;
+        let name = after[..end].trim();
+        if !VARIABLES.contains(&name) {
+            return Err(format!(
+                "Unknown variable {{{{{}}}}} on line {} (available: {})",
+                name,
+                line_of(template, offset),
+                VARIABLES.join(", ")
+            ));
+        }
+        segments.push(Segment::Variable(name));
+        rest = &after[end + 2..];
+    }
+
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+pub fn validate(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// Whether `template` references `{{name}}`.
+pub fn uses(template: &str, name: &str) -> bool {
+    parse(template).is_ok_and(|segments| {
+        segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Variable(variable) if *variable == name))
+    })
+}
+
+pub fn render(template: &str, context: &TemplateContext) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    for segment in parse(template)? {
+        match segment {
+            Segment::Text(text) => rendered.push_str(text),
+            Segment::Variable(name) => rendered.push_str(context.get(name).unwrap_or_default()),
+        }
+    }
+    Ok(rendered)
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::json_file;
+
+/// A word the model should spell a particular way.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VocabularyTerm {
+    /// Preferred spelling, e.g. `Tauri`.
+    pub term: String,
+    /// How it is pronounced or commonly misheard, e.g. `タウリ`.
+    #[serde(default)]
+    pub sounds_like: Vec<String>,
+}
+
+/// User dictionary in `<app config dir>/vocabulary.json`.
+pub struct VocabularyStore {
+    path: PathBuf,
+    terms: Mutex<Vec<VocabularyTerm>>,
+}
+
+impl VocabularyStore {
+    pub fn new(path: PathBuf) -> Self {
+        let terms = json_file::load(&path);
+
+        Self {
+            path,
+            terms: Mutex::new(terms),
+        }
+    }
+
+    pub fn get(&self) -> Vec<VocabularyTerm> {
+        self.terms.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, terms: Vec<VocabularyTerm>) -> Result<(), String> {
+        let terms: Vec<VocabularyTerm> = terms
+            .into_iter()
+            .map(|term| VocabularyTerm {
+                term: term.term.trim().to_string(),
+                sounds_like: term
+                    .sounds_like
+                    .iter()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            })
+            .filter(|term| !term.term.is_empty())
+            .collect();
+
+        json_file::save(&self.path, &terms)?;
+
+        *self.terms.lock().unwrap() = terms;
+        Ok(())
+    }
+
+    /// `prompt` with the dictionary appended as a glossary section.
+    pub fn apply(&self, prompt: String) -> String {
+        let glossary = self.glossary();
+        if glossary.is_empty() {
+            return prompt;
+        }
+        prompt.trim_end().to_string() + "\n\n" + &glossary
+    }
+
+    /// The dictionary as a glossary section, or an empty string without
+    /// terms. Filled into `{{vocabulary}}` in prompt templates.
+    pub fn glossary(&self) -> String {
+        let terms = self.terms.lock().unwrap();
+        if terms.is_empty() {
+            return String::new();
+        }
+
+        let mut glossary =
+            String::from("## 用語集\n以下の用語が話された場合は、必ずこの表記で書いてください。\n");
+        for term in terms.iter() {
+            if term.sounds_like.is_empty() {
+                glossary.push_str(&format!("- {}\n", term.term));
+            } else {
+                glossary.push_str(&format!(
+                    "- {}（読み・聞き間違い: {}）\n",
+                    term.term,
+                    term.sounds_like.join("、")
+                ));
+            }
+        }
+        glossary
+    }
+}
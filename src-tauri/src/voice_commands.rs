@@ -0,0 +1,427 @@
+use regex::{Captures, NoExpand, Regex};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::json_file;
+
+/// What a spoken command does to the transcript.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum CommandAction {
+    /// Replace the phrase with `text`, e.g. a line break or punctuation.
+    Insert { text: String },
+    /// Drop what was said before the phrase in this utterance, or the
+    /// previous insertion if nothing was.
+    DeleteLast,
+}
+
+/// Phrases that trigger an action when they appear in a transcript.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpokenCommand {
+    pub phrases: Vec<String>,
+    pub action: CommandAction,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceCommandSettings {
+    pub enabled: bool,
+    pub commands: Vec<SpokenCommand>,
+}
+
+impl Default for VoiceCommandSettings {
+    /// Off until the user opts in: any transcript that happens to contain a
+    /// phrase would otherwise be rewritten.
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: default_commands(),
+        }
+    }
+}
+
+fn insert(phrases: &[&str], text: &str) -> SpokenCommand {
+    SpokenCommand {
+        phrases: phrases.iter().map(|p| p.to_string()).collect(),
+        action: CommandAction::Insert {
+            text: text.to_string(),
+        },
+    }
+}
+
+pub fn default_commands() -> Vec<SpokenCommand> {
+    vec![
+        insert(&["改段落", "new paragraph"], "\n\n"),
+        insert(&["改行", "new line", "newline"], "\n"),
+        insert(&["句点"], "。"),
+        insert(&["読点"], "、"),
+        insert(&["period", "full stop"], "."),
+        insert(&["comma"], ","),
+        insert(&["クエスチョンマーク", "question mark"], "?"),
+        insert(&["エクスクラメーションマーク", "exclamation mark", "exclamation point"], "!"),
+        SpokenCommand {
+            phrases: vec![
+                "今のを削除".to_string(),
+                "今のを取り消し".to_string(),
+                "delete that".to_string(),
+                "scratch that".to_string(),
+            ],
+            action: CommandAction::DeleteLast,
+        },
+    ]
+}
+
+/// Regex for the phrase itself. English phrases match case-insensitively
+/// with flexible spacing; Japanese ones literally. Whether the match stands
+/// alone is checked separately by [`stands_alone`].
+fn phrase_pattern(phrase: &str) -> Option<String> {
+    let words: Vec<String> = phrase.split_whitespace().map(regex::escape).collect();
+    if words.is_empty() {
+        return None;
+    }
+    if phrase.is_ascii() {
+        Some(format!("(?i:{})", words.join(r"\s+")))
+    } else {
+        Some(words.join(r"\s*"))
+    }
+}
+
+/// Any of `phrases` as capture group 1, with the spaces before it and the
+/// punctuation the model added after it as part of the whole match.
+fn alternation(phrases: &[String]) -> Result<Option<Regex>, String> {
+    let patterns: Vec<String> = phrases
+        .iter()
+        .filter_map(|phrase| phrase_pattern(phrase))
+        .collect();
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    Regex::new(&format!(r"[ \t]*({})[.,!?。、]?", patterns.join("|")))
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Spaces and punctuation separate a phrase from the words around it;
+/// letters, digits, kana and kanji are all alphanumeric.
+fn is_separator(c: Option<char>) -> bool {
+    c.is_none_or(|c| !c.is_alphanumeric())
+}
+
+/// Whether, looking past spaces, `chars` reaches the edge of the text, a
+/// line break or punctuation.
+fn is_anchored(mut chars: impl Iterator<Item = char>) -> bool {
+    chars
+        .find(|c| !matches!(c, ' ' | '\t' | '\u{3000}'))
+        .is_none_or(|c| !c.is_alphanumeric())
+}
+
+/// A phrase counts as a command only when it is not part of a longer word
+/// and is set off by punctuation or the edge of the text on at least one
+/// side, so "the trial period ends" and 句読点 are left alone.
+fn stands_alone(text: &str, start: usize, end: usize) -> bool {
+    let before = &text[..start];
+    let after = &text[end..];
+    is_separator(before.chars().next_back())
+        && is_separator(after.chars().next())
+        && (is_anchored(before.chars().rev()) || is_anchored(after.chars()))
+}
+
+/// Matches of `regex` in `text` whose phrase stands alone, in order.
+fn standalone_matches<'t>(regex: &Regex, text: &'t str) -> Vec<Captures<'t>> {
+    let mut matches = Vec::new();
+    let mut pos = 0;
+    while let Some(caps) = regex.captures_at(text, pos) {
+        let phrase = caps.get(1).expect("phrase group");
+        if stands_alone(text, phrase.start(), phrase.end()) {
+            pos = caps.get(0).map_or(phrase.end(), |m| m.end());
+            matches.push(caps);
+        } else {
+            // Retry from the next character in case a shorter phrase fits
+            let skipped = text[phrase.start()..]
+                .chars()
+                .next()
+                .map_or(1, char::len_utf8);
+            pos = phrase.start() + skipped;
+        }
+        if pos > text.len() {
+            break;
+        }
+    }
+    matches
+}
+
+/// Replace every standalone match of `regex` with `replacement`.
+fn replace_standalone(regex: &Regex, text: &str, replacement: &str) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in standalone_matches(regex, text) {
+        let whole = caps.get(0).expect("whole match");
+        replaced.push_str(&text[last..whole.start()]);
+        replaced.push_str(replacement);
+        last = whole.end();
+    }
+    replaced.push_str(&text[last..]);
+    replaced
+}
+
+/// Result of running the commands over a transcript.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Processed {
+    pub text: String,
+    /// The utterance started with a delete command, so the previous
+    /// insertion should be removed from the focused app.
+    pub delete_previous: bool,
+}
+
+struct CompiledInsert {
+    regex: Regex,
+    text: String,
+    /// Line breaks also swallow the spaces after them.
+    trailing_space: Option<Regex>,
+}
+
+#[derive(Default)]
+pub struct CommandSet {
+    inserts: Vec<CompiledInsert>,
+    delete: Option<Regex>,
+}
+
+impl CommandSet {
+    pub fn compile(commands: &[SpokenCommand]) -> Result<Self, String> {
+        let mut inserts = Vec::new();
+        let mut delete_phrases = Vec::new();
+
+        for command in commands {
+            match &command.action {
+                CommandAction::Insert { text } => {
+                    let Some(regex) = alternation(&command.phrases)? else {
+                        continue;
+                    };
+                    let trailing_space = if text.ends_with('\n') {
+                        Some(
+                            Regex::new(&format!(r"{}[ \t]*", regex::escape(text)))
+                                .map_err(|e| e.to_string())?,
+                        )
+                    } else {
+                        None
+                    };
+                    inserts.push(CompiledInsert {
+                        regex,
+                        text: text.clone(),
+                        trailing_space,
+                    });
+                }
+                CommandAction::DeleteLast => delete_phrases.extend(command.phrases.clone()),
+            }
+        }
+
+        Ok(Self {
+            inserts,
+            delete: alternation(&delete_phrases)?,
+        })
+    }
+
+    pub fn process(&self, text: &str) -> Processed {
+        let mut text = text.to_string();
+        let mut delete_previous = false;
+
+        // Only what follows the last delete command survives
+        if let Some(delete) = &self.delete {
+            let last = standalone_matches(delete, &text)
+                .last()
+                .and_then(|caps| caps.get(0))
+                .map(|m| m.range());
+            if let Some(last) = last {
+                delete_previous = text[..last.start].trim().is_empty();
+                text = text[last.end..].trim_start().to_string();
+            }
+        }
+
+        for insert in &self.inserts {
+            text = replace_standalone(&insert.regex, &text, &insert.text);
+            if let Some(trailing_space) = &insert.trailing_space {
+                text = trailing_space
+                    .replace_all(&text, NoExpand(&insert.text))
+                    .into_owned();
+            }
+        }
+
+        Processed {
+            text: text.trim_start_matches([' ', '\t']).to_string(),
+            delete_previous,
+        }
+    }
+}
+
+/// Spoken command settings in `<app config dir>/voice_commands.json`.
+pub struct VoiceCommandStore {
+    path: PathBuf,
+    settings: Mutex<VoiceCommandSettings>,
+    compiled: Mutex<CommandSet>,
+}
+
+impl VoiceCommandStore {
+    pub fn new(path: PathBuf) -> Self {
+        let settings: VoiceCommandSettings = json_file::load(&path);
+        let compiled = CommandSet::compile(&settings.commands).unwrap_or_else(|e| {
+            eprintln!("Ignoring spoken commands: {}", e);
+            CommandSet::default()
+        });
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+            compiled: Mutex::new(compiled),
+        }
+    }
+
+    pub fn get(&self) -> VoiceCommandSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, settings: VoiceCommandSettings) -> Result<(), String> {
+        let compiled = CommandSet::compile(&settings.commands)?;
+
+        json_file::save(&self.path, &settings)?;
+
+        *self.settings.lock().unwrap() = settings;
+        *self.compiled.lock().unwrap() = compiled;
+        Ok(())
+    }
+
+    pub fn process(&self, text: &str) -> Processed {
+        if !self.settings.lock().unwrap().enabled {
+            return Processed {
+                text: text.to_string(),
+                delete_previous: false,
+            };
+        }
+        self.compiled.lock().unwrap().process(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(text: &str) -> Processed {
+        CommandSet::compile(&default_commands())
+            .unwrap()
+            .process(text)
+    }
+
+    fn text(text: &str) -> String {
+        process(text).text
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(!VoiceCommandSettings::default().enabled);
+    }
+
+    #[test]
+    fn inserts_paragraphs_and_line_breaks() {
+        assert_eq!(text("一段落目。改段落。二段落目"), "一段落目。\n\n二段落目");
+        assert_eq!(text("First. New paragraph. Second"), "First.\n\nSecond");
+        assert_eq!(text("一行目。改行。二行目"), "一行目。\n二行目");
+        assert_eq!(text("First. New line. Second"), "First.\nSecond");
+        assert_eq!(text("First. Newline. Second"), "First.\nSecond");
+    }
+
+    #[test]
+    fn inserts_punctuation() {
+        assert_eq!(text("今日は晴れ 句点"), "今日は晴れ。");
+        assert_eq!(text("はい 読点"), "はい、");
+        assert_eq!(text("I'll be there period"), "I'll be there.");
+        assert_eq!(text("I'll be there full stop"), "I'll be there.");
+        assert_eq!(text("Comma, then more"), ", then more");
+        assert_eq!(text("本当 クエスチョンマーク"), "本当?");
+        assert_eq!(text("Is it done question mark"), "Is it done?");
+        assert_eq!(text("すごい エクスクラメーションマーク"), "すごい!");
+        assert_eq!(text("Great exclamation mark"), "Great!");
+        assert_eq!(text("Great exclamation point"), "Great!");
+    }
+
+    #[test]
+    fn english_phrases_ignore_case_and_spacing() {
+        assert_eq!(text("First. NEW   LINE. Second"), "First.\nSecond");
+        assert_eq!(text("Done Period."), "Done.");
+    }
+
+    #[test]
+    fn leaves_phrases_inside_longer_words() {
+        assert_eq!(text("句読点を揃える"), "句読点を揃える");
+        assert_eq!(text("改行コードをLFにする"), "改行コードをLFにする");
+        assert_eq!(text("一行目改行二行目"), "一行目改行二行目");
+        assert_eq!(text("Periodically check"), "Periodically check");
+        assert_eq!(text("It's periodic"), "It's periodic");
+    }
+
+    #[test]
+    fn leaves_phrases_used_as_ordinary_words() {
+        assert_eq!(text("the trial period ends"), "the trial period ends");
+        assert_eq!(text("Add a comma here"), "Add a comma here");
+        assert_eq!(text("Start a new line of work"), "Start a new line of work");
+    }
+
+    #[test]
+    fn delete_at_start_removes_previous_insertion() {
+        let processed = process("Delete that. Hello");
+        assert_eq!(processed.text, "Hello");
+        assert!(processed.delete_previous);
+
+        let processed = process("今のを削除");
+        assert_eq!(processed.text, "");
+        assert!(processed.delete_previous);
+
+        let processed = process("Scratch that");
+        assert_eq!(processed.text, "");
+        assert!(processed.delete_previous);
+    }
+
+    #[test]
+    fn delete_in_middle_drops_earlier_text_only() {
+        let processed = process("Hi there. Scratch that. Bye");
+        assert_eq!(processed.text, "Bye");
+        assert!(!processed.delete_previous);
+
+        let processed = process("こんにちは。今のを取り消し。さようなら");
+        assert_eq!(processed.text, "さようなら");
+        assert!(!processed.delete_previous);
+    }
+
+    #[test]
+    fn delete_inside_a_sentence_is_ignored() {
+        let processed = process("please delete that file");
+        assert_eq!(processed.text, "please delete that file");
+        assert!(!processed.delete_previous);
+    }
+
+    #[test]
+    fn line_breaks_swallow_following_spaces() {
+        assert_eq!(
+            text("First item. New line.   Second item"),
+            "First item.\nSecond item"
+        );
+        assert_eq!(text("New line. Indented"), "\nIndented");
+        assert_eq!(text("Para. New paragraph.\tNext"), "Para.\n\nNext");
+    }
+
+    #[test]
+    fn plain_text_is_unchanged() {
+        assert_eq!(text("plain text"), "plain text");
+        assert_eq!(text(""), "");
+    }
+
+    #[test]
+    fn disabled_store_passes_text_through() {
+        let path =
+            std::env::temp_dir().join(format!("voice-commands-test-{}.json", std::process::id()));
+        let store = VoiceCommandStore::new(path);
+        let processed = store.process("Done period");
+        assert_eq!(processed.text, "Done period");
+        assert!(!processed.delete_previous);
+    }
+}
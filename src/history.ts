@@ -0,0 +1,31 @@
+// Mirrors `HistoryEntry` in src-tauri/src/history.rs
+export interface HistoryEntry {
+  id: number;
+  timestampMs: number;
+  audioDurationMs: number;
+  latencyMs: number;
+  model: string;
+  profileId: string;
+  // Focused app when recording started; null when unknown
+  app: string | null;
+  text: string;
+}
+
+// Mirrors `ArchiveSettings` in src-tauri/src/archive.rs
+export interface ArchiveSettings {
+  enabled: boolean;
+  maxMegabytes: number | null;
+}
+
+// Mirrors `ContinuitySettings` in src-tauri/src/history.rs
+export interface ContinuitySettings {
+  enabled: boolean;
+  maxTranscripts: number;
+  windowSecs: number;
+}
+
+// Mirrors `HistoryRetention`; null means no limit
+export interface HistoryRetention {
+  maxEntries: number | null;
+  maxAgeDays: number | null;
+}
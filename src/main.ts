@@ -1,15 +1,149 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { loadSettings, saveSettings, Settings } from './settings';
+import {
+  loadSettings,
+  saveSettings,
+  DEFAULT_SETTINGS,
+  AppContextRule,
+  Language,
+  NormalizeSettings,
+  Profile,
+  Settings,
+} from './settings';
+import { PartialTranscript, Status, StatusKind } from './status';
+import { HistoryEntry } from './history';
 import './styles.css';
 
 let settings: Settings;
+let profiles: Profile[] = [];
+// Built-in profiles come back on every start, so they can't be removed
+let builtInProfileIds = new Set<string>();
+let rules: ReplacementRule[] = [];
+let voiceCommands: VoiceCommandSettings = { enabled: false, commands: [] };
+
+const DEFAULT_PROFILE = 'default';
+// Needs a selection, so it only runs from the edit-selection action
+const EDIT_PROFILE = 'edit-selection';
+
+// Mirrors `ShortcutAction` in src-tauri/src/shortcuts.rs
+type ShortcutAction =
+  | { type: 'dictate'; profileId: string }
+  | { type: 'editSelection'; profileId: string }
+  | { type: 'cancel' }
+  | { type: 'repasteLast' }
+  | { type: 'openHistory' }
+  | { type: 'toggleHandsFree'; profileId: string };
+
+// Mirrors `VocabularyTerm` in src-tauri/src/vocabulary.rs
+interface VocabularyTerm {
+  term: string;
+  soundsLike: string[];
+}
+
+// Mirrors `ReplacementRule` in src-tauri/src/rules.rs
+interface ReplacementRule {
+  kind: 'literal' | 'regex';
+  pattern: string;
+  replacement: string;
+  caseInsensitive: boolean;
+  wholeWord: boolean;
+  profiles: string[];
+  apps: string[];
+  enabled: boolean;
+}
+
+// Mirrors `VoiceCommandSettings` in src-tauri/src/voice_commands.rs
+interface SpokenCommand {
+  phrases: string[];
+  action: { type: 'insert'; text: string } | { type: 'deleteLast' };
+}
+
+interface VoiceCommandSettings {
+  enabled: boolean;
+  commands: SpokenCommand[];
+}
+
+// Mirrors `ShortcutError` in src-tauri/src/shortcuts.rs
+type ShortcutError =
+  | { kind: 'invalid'; message: string }
+  | { kind: 'conflict'; shortcut: string; bindingId: string }
+  | { kind: 'unavailable'; shortcut: string; message: string }
+  | { kind: 'failed'; message: string };
+
+// Mirrors `ShortcutBinding` in src-tauri/src/shortcuts.rs
+interface ShortcutBinding {
+  id: string;
+  shortcut: string;
+  action: ShortcutAction;
+}
+
+// Non-dictation actions, keyed by binding ID
+const ACTIONS: { id: string; label: string; action: ShortcutAction }[] = [
+  { id: 'cancel', label: 'Cancel', action: { type: 'cancel' } },
+  { id: 'repaste-last', label: 'Paste last again', action: { type: 'repasteLast' } },
+  { id: 'open-history', label: 'Open history', action: { type: 'openHistory' } },
+  {
+    id: 'toggle-hands-free',
+    label: 'Toggle hands-free',
+    action: { type: 'toggleHandsFree', profileId: DEFAULT_PROFILE },
+  },
+  {
+    id: 'edit-selection',
+    label: 'Edit selection by voice',
+    action: { type: 'editSelection', profileId: EDIT_PROFILE },
+  },
+];
 
 // UI Elements
 const apiKeyInput = document.getElementById('api-key') as HTMLInputElement;
 const modelInput = document.getElementById('model') as HTMLInputElement;
+const streamingInput = document.getElementById('streaming') as HTMLInputElement;
+const typeProgressivelyInput = document.getElementById('type-progressively') as HTMLInputElement;
+const temperatureInput = document.getElementById('temperature') as HTMLInputElement;
+const maxOutputTokensInput = document.getElementById('max-output-tokens') as HTMLInputElement;
+const thinkingBudgetInput = document.getElementById('thinking-budget') as HTMLInputElement;
+const useSystemInstructionInput = document.getElementById('use-system-instruction') as HTMLInputElement;
+const languageSelect = document.getElementById('language') as HTMLSelectElement;
+const continuityEnabledInput = document.getElementById('continuity-enabled') as HTMLInputElement;
+const continuityMaxTranscriptsInput = document.getElementById('continuity-max-transcripts') as HTMLInputElement;
+const continuityWindowInput = document.getElementById('continuity-window') as HTMLInputElement;
+const translationSourceInput = document.getElementById('translation-source') as HTMLInputElement;
+const translationTargetInput = document.getElementById('translation-target') as HTMLInputElement;
+const translationIncludeOriginalInput = document.getElementById('translation-include-original') as HTMLInputElement;
+const normalizeWidthSelect = document.getElementById('normalize-width') as HTMLSelectElement;
+const normalizePunctuationSelect = document.getElementById('normalize-punctuation') as HTMLSelectElement;
+const normalizeSpacingSelect = document.getElementById('normalize-spacing') as HTMLSelectElement;
+const normalizeHalfWidthSpaceInput = document.getElementById('normalize-half-width-space') as HTMLInputElement;
+const profilesList = document.getElementById('profiles-list') as HTMLDivElement;
+const addProfileBtn = document.getElementById('add-profile') as HTMLButtonElement;
+const promptProfileSelect = document.getElementById('prompt-profile') as HTMLSelectElement;
+const promptInput = document.getElementById('prompt') as HTMLTextAreaElement;
+const promptError = document.getElementById('prompt-error') as HTMLDivElement;
+const vocabularyInput = document.getElementById('vocabulary') as HTMLTextAreaElement;
+const rulesList = document.getElementById('rules-list') as HTMLDivElement;
+const appRulesList = document.getElementById('app-rules-list') as HTMLDivElement;
+const addAppRuleBtn = document.getElementById('add-app-rule') as HTMLButtonElement;
+const voiceCommandsEnabledInput = document.getElementById('voice-commands-enabled') as HTMLInputElement;
+const voiceCommandsList = document.getElementById('voice-commands-list') as HTMLDivElement;
+const addVoiceCommandBtn = document.getElementById('add-voice-command') as HTMLButtonElement;
+const addRuleBtn = document.getElementById('add-rule') as HTMLButtonElement;
+const rulesTestInput = document.getElementById('rules-test-input') as HTMLTextAreaElement;
+const rulesTestOutput = document.getElementById('rules-test-output') as HTMLDivElement;
 const shortcutDisplay = document.getElementById('shortcut-display') as HTMLSpanElement;
 const setShortcutBtn = document.getElementById('set-shortcut') as HTMLButtonElement;
+const profileShortcutsContainer = document.getElementById('profile-shortcuts') as HTMLDivElement;
+const actionShortcutsContainer = document.getElementById('action-shortcuts') as HTMLDivElement;
+const pushToTalkInput = document.getElementById('push-to-talk') as HTMLInputElement;
+const pushToTalkKeySelect = document.getElementById('push-to-talk-key') as HTMLSelectElement;
+const historyCard = document.getElementById('history-card') as HTMLDivElement;
+const historySearchInput = document.getElementById('history-search') as HTMLInputElement;
+const historyList = document.getElementById('history-list') as HTMLDivElement;
+const retranscribeProfileSelect = document.getElementById('retranscribe-profile') as HTMLSelectElement;
+const retranscribeModelInput = document.getElementById('retranscribe-model') as HTMLInputElement;
+const archiveEnabledInput = document.getElementById('archive-enabled') as HTMLInputElement;
+const archiveMaxMegabytesInput = document.getElementById('archive-max-megabytes') as HTMLInputElement;
+const historyMaxEntriesInput = document.getElementById('history-max-entries') as HTMLInputElement;
+const historyMaxAgeDaysInput = document.getElementById('history-max-age-days') as HTMLInputElement;
 const saveBtn = document.getElementById('save-settings') as HTMLButtonElement;
 const statusIndicator = document.getElementById('status-indicator') as HTMLDivElement;
 const statusText = document.getElementById('status-text') as HTMLSpanElement;
@@ -21,7 +155,31 @@ async function init() {
   // Populate UI
   apiKeyInput.value = settings.apiKey;
   modelInput.value = settings.model;
+  streamingInput.checked = settings.streaming;
+  typeProgressivelyInput.checked = settings.typeProgressively;
+  typeProgressivelyInput.disabled = !settings.streaming;
+  temperatureInput.value = formatOptionalNumber(settings.generationConfig.temperature);
+  maxOutputTokensInput.value = formatOptionalNumber(settings.generationConfig.maxOutputTokens);
+  thinkingBudgetInput.value = formatOptionalNumber(settings.generationConfig.thinkingBudget);
+  useSystemInstructionInput.checked = settings.useSystemInstruction;
+  languageSelect.value = settings.language;
+  continuityEnabledInput.checked = settings.continuity.enabled;
+  continuityMaxTranscriptsInput.value = String(settings.continuity.maxTranscripts);
+  continuityWindowInput.value = String(settings.continuity.windowSecs);
+  translationSourceInput.value = settings.translation.sourceLanguage;
+  translationTargetInput.value = settings.translation.targetLanguage;
+  translationIncludeOriginalInput.checked = settings.translation.includeOriginal;
+  normalizeWidthSelect.value = settings.normalize.alphanumericWidth;
+  normalizePunctuationSelect.value = settings.normalize.punctuation;
+  normalizeSpacingSelect.value = settings.normalize.spacing;
+  normalizeHalfWidthSpaceInput.checked = settings.normalize.halfWidthSpace;
   shortcutDisplay.textContent = settings.shortcut || 'Not set';
+  pushToTalkInput.checked = settings.pushToTalk.enabled;
+  pushToTalkKeySelect.value = settings.pushToTalk.key;
+  historyMaxEntriesInput.value = formatOptionalNumber(settings.historyRetention.maxEntries);
+  historyMaxAgeDaysInput.value = formatOptionalNumber(settings.historyRetention.maxAgeDays);
+  archiveEnabledInput.checked = settings.archive.enabled;
+  archiveMaxMegabytesInput.value = formatOptionalNumber(settings.archive.maxMegabytes);
 
   // Set API key in Rust backend
   if (settings.apiKey) {
@@ -33,40 +191,752 @@ async function init() {
     await invoke('set_model', { model: settings.model });
   }
   
-  // Register shortcut in Rust backend if exists
-  if (settings.shortcut) {
-    await registerShortcutInBackend(settings.shortcut);
+  await invoke('set_generation_config', { config: settings.generationConfig });
+  await invoke('set_use_system_instruction', { enabled: settings.useSystemInstruction });
+  await invoke('set_language', { language: settings.language });
+  await invoke('set_continuity_settings', { settings: settings.continuity });
+  await invoke('set_translation_settings', { settings: settings.translation });
+  await invoke('set_normalize_settings', { settings: settings.normalize });
+  await invoke('set_app_rules', { rules: settings.appRules });
+
+  await invoke('set_streaming', {
+    streaming: settings.streaming,
+    typeProgressively: settings.typeProgressively,
+  });
+
+  try {
+    await invoke('set_profiles', { profiles: settings.profiles });
+  } catch (error) {
+    console.error('Ignoring saved profiles:', error);
+  }
+  profiles = await invoke<Profile[]>('get_profiles');
+  const defaultProfiles = await invoke<Profile[]>('get_default_profiles');
+  builtInProfileIds = new Set(defaultProfiles.map((profile) => profile.id));
+  renderProfileEditor();
+  renderProfiles();
+  renderActionShortcuts();
+  renderAppRules();
+  await loadPrompt();
+  vocabularyInput.value = formatVocabulary(await invoke<VocabularyTerm[]>('get_vocabulary'));
+  rules = await invoke<ReplacementRule[]>('get_rules');
+  renderRules();
+  voiceCommands = await invoke<VoiceCommandSettings>('get_voice_commands');
+  voiceCommandsEnabledInput.checked = voiceCommands.enabled;
+  renderVoiceCommands();
+
+  await invoke('set_history_retention', { retention: settings.historyRetention });
+  await invoke('set_archive_settings', { settings: settings.archive });
+  await loadHistory();
+
+  // Register shortcuts in Rust backend
+  const shortcutFailures = await registerAllShortcuts(false);
+
+  // A missing input permission shouldn't block the rest of startup
+  try {
+    await invoke('set_push_to_talk', { config: settings.pushToTalk });
+  } catch (error) {
+    console.error('Failed to start push-to-talk:', error);
   }
   
-  updateStatus('idle');
+  if (shortcutFailures.length > 0) {
+    updateStatus('error', `Shortcut error: ${shortcutFailures.join('; ')}`);
+  } else {
+    updateStatus('idle');
+  }
 
   // Listen to status changes from Rust backend
-  await listen<string>('status-changed', (event) => {
+  await listen<Status>('status-changed', (event) => {
     const status = event.payload;
     console.log('Status changed:', status);
 
-    if (status.startsWith('error:')) {
-      const errorMessage = status.substring(6);
-      updateStatus('error', errorMessage);
-    } else {
-      updateStatus(status as 'idle' | 'recording' | 'processing' | 'transcribing' | 'success' | 'error');
+    switch (status.kind) {
+      case 'success':
+        updateStatus('success', `✅ Done (${formatSeconds(status.elapsedMs)})`);
+        break;
+      case 'error':
+        updateStatus('error', status.message);
+        break;
+      default:
+        updateStatus(status.kind);
     }
   });
+
+  // Refresh the editor when the prompt file is edited outside the app
+  await listen<string>('prompt-changed', async (event) => {
+    if (event.payload === selectedPromptName()) {
+      await loadPrompt();
+    }
+  });
+
+  await listen('history-changed', loadHistory);
+  // The tray's Language submenu changes the backend directly; keep the
+  // form and settings.json in step
+  await listen<Language>('language-changed', async (event) => {
+    settings.language = event.payload;
+    languageSelect.value = event.payload;
+    await saveSettings(settings);
+  });
+  await listen('open-history', () => {
+    historyCard.scrollIntoView({ behavior: 'smooth' });
+    historySearchInput.focus();
+  });
+
+  // Show streamed text while the response is still arriving
+  await listen<PartialTranscript>('transcript-partial', (event) => {
+    statusText.textContent = event.payload.text;
+  });
 }
 
-async function registerShortcutInBackend(shortcut: string) {
+const HISTORY_LIMIT = 100;
+
+async function loadHistory() {
+  const query = historySearchInput.value.trim();
   try {
-    await invoke('register_shortcut', { shortcutStr: shortcut });
-    console.log(`Registered shortcut in backend: ${shortcut}`);
-    updateStatus('success', 'Shortcut registered!');
+    const entries = query
+      ? await invoke<HistoryEntry[]>('search_history', { query, limit: HISTORY_LIMIT })
+      : await invoke<HistoryEntry[]>('list_history', { limit: HISTORY_LIMIT });
+    const archived = await invoke<number[]>('list_archived_recordings');
+    renderHistory(entries, new Set(archived));
+  } catch (error) {
+    console.error('Failed to load history:', error);
+  }
+}
+
+function renderHistory(entries: HistoryEntry[], archived: Set<number>) {
+  if (entries.length === 0) {
+    const empty = document.createElement('div');
+    empty.className = 'history-empty';
+    empty.textContent = historySearchInput.value.trim() ? 'No matches' : 'No transcripts yet';
+    historyList.replaceChildren(empty);
+    return;
+  }
+  historyList.replaceChildren(
+    ...entries.map((entry) => renderHistoryEntry(entry, archived.has(entry.id))),
+  );
+}
+
+function renderHistoryEntry(entry: HistoryEntry, hasRecording: boolean): HTMLDivElement {
+  const item = document.createElement('div');
+  item.className = 'history-entry';
+
+  const meta = document.createElement('div');
+  meta.className = 'history-meta';
+
+  const profile = profiles.find((p) => p.id === entry.profileId);
+  const info = document.createElement('span');
+  info.textContent = [
+    new Date(entry.timestampMs).toLocaleString(),
+    profile ? profile.name : entry.profileId,
+    entry.model,
+    `${formatSeconds(entry.audioDurationMs)} audio`,
+    `${formatSeconds(entry.latencyMs)} latency`,
+  ].join(' · ');
+
+  const buttons = document.createElement('div');
+  const copyBtn = document.createElement('button');
+  copyBtn.className = 'btn btn-secondary';
+  copyBtn.textContent = 'Copy';
+  copyBtn.addEventListener('click', async () => {
+    await navigator.clipboard.writeText(entry.text);
+    updateStatus('success', 'Copied to clipboard');
     setTimeout(() => updateStatus('idle'), 2000);
+  });
+
+  const deleteBtn = document.createElement('button');
+  deleteBtn.className = 'btn btn-secondary';
+  deleteBtn.textContent = 'Delete';
+  deleteBtn.addEventListener('click', async () => {
+    try {
+      await invoke('delete_history', { ids: [entry.id] });
+    } catch (error) {
+      updateStatus('error', `Delete failed: ${error}`);
+    }
+  });
+
+  const text = document.createElement('div');
+  text.className = 'history-text';
+  text.textContent = entry.text;
+
+  buttons.append(copyBtn);
+  if (hasRecording) {
+    const retranscribeBtn = document.createElement('button');
+    retranscribeBtn.className = 'btn btn-secondary';
+    retranscribeBtn.textContent = 'Re-transcribe';
+    retranscribeBtn.addEventListener('click', () => retranscribe(entry));
+    buttons.append(retranscribeBtn);
+  }
+  buttons.append(deleteBtn);
+  meta.append(info, buttons);
+  item.append(meta, text);
+  return item;
+}
+
+async function retranscribe(entry: HistoryEntry) {
+  updateStatus('transcribing');
+  try {
+    await invoke<HistoryEntry>('retranscribe', {
+      id: entry.id,
+      profileId: retranscribeProfileSelect.value || null,
+      model: retranscribeModelInput.value.trim() || null,
+    });
+    updateStatus('success', 'Re-transcribed; see the newest history entry');
+  } catch (error) {
+    updateStatus('error', `Re-transcription failed: ${error}`);
+  }
+  setTimeout(() => updateStatus('idle'), 2000);
+}
+
+function renderRules() {
+  rulesList.replaceChildren(...rules.map(renderRule));
+  testRules();
+}
+
+function renderRule(rule: ReplacementRule, index: number): HTMLDivElement {
+  const row = document.createElement('div');
+  row.className = 'rule-row';
+
+  const kind = document.createElement('select');
+  kind.append(new Option('Text', 'literal'), new Option('Regex', 'regex'));
+  kind.value = rule.kind;
+  kind.addEventListener('change', () => {
+    rule.kind = kind.value as ReplacementRule['kind'];
+    testRules();
+  });
+
+  const pattern = document.createElement('input');
+  pattern.type = 'text';
+  pattern.placeholder = 'Find';
+  pattern.value = rule.pattern;
+  pattern.addEventListener('input', () => {
+    rule.pattern = pattern.value;
+    testRules();
+  });
+
+  const replacement = document.createElement('input');
+  replacement.type = 'text';
+  replacement.placeholder = 'Replace with';
+  replacement.value = rule.replacement;
+  replacement.addEventListener('input', () => {
+    rule.replacement = replacement.value;
+    testRules();
+  });
+
+  const profile = document.createElement('select');
+  profile.append(
+    new Option('All profiles', ''),
+    ...profiles.map((p) => new Option(p.name, p.id)),
+  );
+  profile.value = rule.profiles[0] ?? '';
+  profile.addEventListener('change', () => {
+    rule.profiles = profile.value ? [profile.value] : [];
+    testRules();
+  });
+
+  const apps = document.createElement('input');
+  apps.type = 'text';
+  apps.placeholder = 'All apps';
+  apps.value = rule.apps.join(', ');
+  apps.addEventListener('input', () => {
+    rule.apps = apps.value
+      .split(',')
+      .map((app) => app.trim())
+      .filter((app) => app !== '');
+  });
+
+  const remove = document.createElement('button');
+  remove.className = 'btn btn-secondary';
+  remove.textContent = 'Remove';
+  remove.addEventListener('click', () => {
+    rules.splice(index, 1);
+    renderRules();
+  });
+
+  row.append(
+    kind,
+    pattern,
+    replacement,
+    ruleCheckbox('Ignore case', rule.caseInsensitive, (checked) => (rule.caseInsensitive = checked)),
+    ruleCheckbox('Whole word', rule.wholeWord, (checked) => (rule.wholeWord = checked)),
+    ruleCheckbox('On', rule.enabled, (checked) => (rule.enabled = checked)),
+    profile,
+    apps,
+    remove,
+  );
+  return row;
+}
+
+// Text style that leaves the transcript untouched, e.g. for terminals
+const RAW_TEXT: NormalizeSettings = {
+  alphanumericWidth: 'keep',
+  punctuation: 'keep',
+  spacing: 'keep',
+  halfWidthSpace: false,
+};
+
+function renderAppRules() {
+  appRulesList.replaceChildren(...settings.appRules.map(renderAppRule));
+}
+
+function renderAppRule(rule: AppContextRule, index: number): HTMLDivElement {
+  const row = document.createElement('div');
+  row.className = 'rule-row';
+
+  const app = document.createElement('input');
+  app.type = 'text';
+  app.placeholder = 'com.apple.Terminal';
+  app.value = rule.app;
+  app.addEventListener('input', () => {
+    rule.app = app.value.trim();
+  });
+
+  const profile = document.createElement('select');
+  profile.append(
+    new Option('Default profile', ''),
+    ...profiles.map((p) => new Option(p.name, p.id)),
+  );
+  profile.value = rule.profileId ?? '';
+  profile.addEventListener('change', () => {
+    rule.profileId = profile.value || null;
+  });
+
+  const style = document.createElement('select');
+  style.append(new Option('Global text style', 'global'), new Option('Raw text', 'raw'));
+  style.value = rule.normalize ? 'raw' : 'global';
+  style.addEventListener('change', () => {
+    rule.normalize = style.value === 'raw' ? { ...RAW_TEXT } : null;
+  });
+
+  const remove = document.createElement('button');
+  remove.className = 'btn btn-secondary';
+  remove.textContent = 'Remove';
+  remove.addEventListener('click', () => {
+    settings.appRules.splice(index, 1);
+    renderAppRules();
+  });
+
+  row.append(app, profile, style, remove);
+  return row;
+}
+
+function ruleCheckbox(text: string, checked: boolean, set: (checked: boolean) => void) {
+  const label = document.createElement('label');
+  const input = document.createElement('input');
+  input.type = 'checkbox';
+  input.checked = checked;
+  input.addEventListener('change', () => {
+    set(input.checked);
+    testRules();
+  });
+  label.append(input, text);
+  return label;
+}
+
+async function testRules() {
+  const text = rulesTestInput.value;
+  if (!text) {
+    rulesTestOutput.textContent = '';
+    return;
+  }
+  try {
+    rulesTestOutput.textContent = await invoke<string>('test_rules', {
+      rules,
+      text,
+      profileId: null,
+    });
+  } catch (error) {
+    rulesTestOutput.textContent = `${error}`;
+  }
+}
+
+function renderVoiceCommands() {
+  voiceCommandsList.replaceChildren(...voiceCommands.commands.map(renderVoiceCommand));
+}
+
+function renderVoiceCommand(command: SpokenCommand, index: number): HTMLDivElement {
+  const row = document.createElement('div');
+  row.className = 'rule-row';
+
+  const action = document.createElement('input');
+  action.type = 'text';
+  if (command.action.type === 'insert') {
+    const insert = command.action;
+    action.value = insert.text.replace(/\n/g, '\\n');
+    action.addEventListener('input', () => {
+      insert.text = action.value.replace(/\\n/g, '\n');
+    });
+  } else {
+    action.value = 'Delete last';
+    action.disabled = true;
+  }
+
+  const phrases = document.createElement('input');
+  phrases.type = 'text';
+  phrases.value = command.phrases.join(', ');
+  phrases.addEventListener('input', () => {
+    command.phrases = phrases.value
+      .split(/[,、]/)
+      .map((p) => p.trim())
+      .filter((p) => p !== '');
+  });
+
+  const remove = document.createElement('button');
+  remove.className = 'btn btn-secondary';
+  remove.textContent = 'Remove';
+  remove.addEventListener('click', () => {
+    voiceCommands.commands.splice(index, 1);
+    renderVoiceCommands();
+  });
+
+  row.append(action, phrases, remove);
+  return row;
+}
+
+// `Term = sound, sound` per line
+function formatVocabulary(terms: VocabularyTerm[]): string {
+  return terms
+    .map(({ term, soundsLike }) => (soundsLike.length ? `${term} = ${soundsLike.join(', ')}` : term))
+    .join('\n');
+}
+
+function parseVocabulary(text: string): VocabularyTerm[] {
+  return text
+    .split('\n')
+    .map((line) => {
+      const [term, sounds = ''] = line.split('=', 2);
+      return {
+        term: term.trim(),
+        soundsLike: sounds
+          .split(/[,、]/)
+          .map((s) => s.trim())
+          .filter((s) => s !== ''),
+      };
+    })
+    .filter(({ term }) => term !== '');
+}
+
+function formatOptionalNumber(value: number | null): string {
+  return value === null ? '' : String(value);
+}
+
+function parseOptionalNumber(value: string): number | null {
+  const trimmed = value.trim();
+  if (trimmed === '') return null;
+  const parsed = Number(trimmed);
+  return Number.isFinite(parsed) ? parsed : null;
+}
+
+function formatSeconds(ms: number): string {
+  return `${(ms / 1000).toFixed(1)}s`;
+}
+
+function selectedPromptName(): string {
+  const profile = profiles.find((p) => p.id === promptProfileSelect.value);
+  return profile ? profile.prompt : 'transcription';
+}
+
+async function loadPrompt() {
+  try {
+    promptInput.value = await invoke<string>('get_prompt', { name: selectedPromptName() });
+  } catch (error) {
+    console.error('Failed to load prompt:', error);
+  }
+  await validatePrompt();
+}
+
+async function validatePrompt() {
+  try {
+    await invoke('validate_prompt', { text: promptInput.value });
+    promptError.textContent = '';
+  } catch (error) {
+    promptError.textContent = `${error}`;
+  }
+}
+
+function renderProfiles() {
+  // Keep the current choices when re-rendering after a save
+  const promptProfile = promptProfileSelect.value;
+  const retranscribeProfile = retranscribeProfileSelect.value;
+  promptProfileSelect.replaceChildren(
+    ...profiles.map((profile) => new Option(profile.name, profile.id)),
+  );
+  retranscribeProfileSelect.replaceChildren(
+    new Option("Entry's profile", ''),
+    ...profiles
+      .filter((profile) => profile.id !== EDIT_PROFILE)
+      .map((profile) => new Option(profile.name, profile.id)),
+  );
+
+  // The default profile uses the main shortcut above, and the edit profile
+  // the edit-selection action
+  profileShortcutsContainer.replaceChildren(
+    ...profiles
+      .filter((profile) => profile.id !== DEFAULT_PROFILE && profile.id !== EDIT_PROFILE)
+      .map((profile) => renderProfileShortcut(profile)),
+  );
+
+  if (profiles.some((profile) => profile.id === promptProfile)) {
+    promptProfileSelect.value = promptProfile;
+  }
+  if (profiles.some((profile) => profile.id === retranscribeProfile)) {
+    retranscribeProfileSelect.value = retranscribeProfile;
+  }
+}
+
+function setProfileShortcut(profileId: string, shortcut: string | undefined) {
+  if (shortcut) {
+    settings.profileShortcuts[profileId] = shortcut;
+  } else {
+    delete settings.profileShortcuts[profileId];
+  }
+}
+
+function setActionShortcut(id: string, shortcut: string | undefined) {
+  if (shortcut) {
+    settings.actionShortcuts[id] = shortcut;
+  } else {
+    delete settings.actionShortcuts[id];
+  }
+}
+
+function renderProfileEditor() {
+  profilesList.replaceChildren(...profiles.map(renderProfileRow));
+}
+
+function renderProfileRow(profile: Profile, index: number): HTMLDivElement {
+  const row = document.createElement('div');
+  row.className = 'rule-row';
+
+  const name = document.createElement('input');
+  name.type = 'text';
+  name.placeholder = 'Name';
+  name.value = profile.name;
+  name.addEventListener('input', () => {
+    profile.name = name.value;
+  });
+
+  const prompt = document.createElement('input');
+  prompt.type = 'text';
+  prompt.placeholder = 'Prompt file';
+  prompt.value = profile.prompt;
+  prompt.addEventListener('input', () => {
+    profile.prompt = prompt.value.trim();
+  });
+
+  const model = document.createElement('input');
+  model.type = 'text';
+  model.placeholder = 'Global model';
+  model.value = profile.model ?? '';
+  model.addEventListener('input', () => {
+    profile.model = model.value.trim() || null;
+  });
+
+  const output = document.createElement('select');
+  output.append(new Option('Paste', 'paste'), new Option('Clipboard only', 'clipboard'));
+  output.value = profile.output;
+  output.addEventListener('change', () => {
+    profile.output = output.value as Profile['output'];
+  });
+
+  const voiceCommandsLabel = document.createElement('label');
+  const voiceCommandsInput = document.createElement('input');
+  voiceCommandsInput.type = 'checkbox';
+  voiceCommandsInput.checked = profile.voiceCommands ?? profile.id === DEFAULT_PROFILE;
+  voiceCommandsInput.addEventListener('change', () => {
+    profile.voiceCommands = voiceCommandsInput.checked;
+  });
+  voiceCommandsLabel.append(voiceCommandsInput, 'Spoken commands');
+
+  row.append(name, prompt, model, output, voiceCommandsLabel);
+  if (!builtInProfileIds.has(profile.id)) {
+    const remove = document.createElement('button');
+    remove.className = 'btn btn-secondary';
+    remove.textContent = 'Remove';
+    remove.addEventListener('click', async () => {
+      profiles.splice(index, 1);
+      setProfileShortcut(profile.id, undefined);
+      renderProfileEditor();
+      try {
+        await invoke('unregister_shortcut', { id: dictateBindingId(profile.id) });
+      } catch (error) {
+        updateStatus('error', `Shortcut error: ${describeShortcutError(error as ShortcutError)}`);
+      }
+    });
+    row.append(remove);
+  }
+  return row;
+}
+
+function renderProfileShortcut(profile: Profile): HTMLDivElement {
+  return renderShortcutRow(
+    profile.name,
+    () => settings.profileShortcuts[profile.id],
+    (shortcut) => setProfileShortcut(profile.id, shortcut),
+    dictateBindingId(profile.id),
+  );
+}
+
+function renderActionShortcuts() {
+  actionShortcutsContainer.replaceChildren(
+    ...ACTIONS.map(({ id, label }) =>
+      renderShortcutRow(
+        label,
+        () => settings.actionShortcuts[id],
+        (shortcut) => setActionShortcut(id, shortcut),
+        id,
+      ),
+    ),
+  );
+}
+
+function renderShortcuts() {
+  shortcutDisplay.textContent = settings.shortcut || 'Not set';
+  renderProfiles();
+  renderActionShortcuts();
+}
+
+function renderShortcutRow(
+  labelText: string,
+  get: () => string | undefined,
+  set: (shortcut: string | undefined) => void,
+  bindingId: string,
+): HTMLDivElement {
+  const group = document.createElement('div');
+  group.className = 'form-group';
+
+  const label = document.createElement('label');
+  label.textContent = labelText;
+
+  const row = document.createElement('div');
+  row.className = 'shortcut-display';
+
+  const display = document.createElement('span');
+  display.textContent = get() || 'Not set';
+
+  const buttons = document.createElement('div');
+  const changeBtn = document.createElement('button');
+  changeBtn.className = 'btn btn-secondary';
+  changeBtn.textContent = 'Change';
+  changeBtn.addEventListener('click', async () => {
+    display.textContent = 'Press keys...';
+    const shortcut = await captureShortcut();
+    display.textContent = shortcut;
+    set(shortcut);
+  });
+
+  const clearBtn = document.createElement('button');
+  clearBtn.className = 'btn btn-secondary';
+  clearBtn.textContent = 'Clear';
+  clearBtn.addEventListener('click', async () => {
+    display.textContent = 'Not set';
+    set(undefined);
+    try {
+      await invoke('unregister_shortcut', { id: bindingId });
+    } catch (error) {
+      updateStatus('error', `Shortcut error: ${describeShortcutError(error as ShortcutError)}`);
+    }
+  });
+
+  buttons.append(changeBtn, clearBtn);
+  row.append(display, buttons);
+  group.append(label, row);
+  return group;
+}
+
+function dictateBindingId(profileId: string): string {
+  return `dictate:${profileId}`;
+}
+
+// Every shortcut the settings ask for, with the setter for its setting
+function configuredShortcuts(): {
+  id: string;
+  shortcut: string | undefined;
+  action: ShortcutAction;
+  set: (shortcut: string | undefined) => void;
+}[] {
+  return [
+    {
+      id: dictateBindingId(DEFAULT_PROFILE),
+      shortcut: settings.shortcut || undefined,
+      action: { type: 'dictate', profileId: DEFAULT_PROFILE },
+      set: (shortcut) => (settings.shortcut = shortcut ?? ''),
+    },
+    ...Object.entries(settings.profileShortcuts)
+      .filter(([profileId]) => profileId !== EDIT_PROFILE)
+      .map(([profileId, shortcut]) => ({
+        id: dictateBindingId(profileId),
+        shortcut,
+        action: { type: 'dictate', profileId } as ShortcutAction,
+        set: (shortcut: string | undefined) => setProfileShortcut(profileId, shortcut),
+      })),
+    ...ACTIONS.map(({ id, action }) => ({
+      id,
+      shortcut: settings.actionShortcuts[id],
+      action,
+      set: (shortcut: string | undefined) => setActionShortcut(id, shortcut),
+    })),
+  ];
+}
+
+// Register every configured shortcut and describe the ones that failed.
+// With `rollBack`, a rejected shortcut goes back to whatever is still
+// registered for its binding (the backend keeps the old one), so only
+// working bindings end up in settings.json.
+async function registerAllShortcuts(rollBack: boolean): Promise<string[]> {
+  const bindings = await invoke<ShortcutBinding[]>('list_shortcuts');
+  const registered = new Map(
+    bindings.map((binding): [string, string] => [binding.id, binding.shortcut]),
+  );
+  const failures: string[] = [];
+  for (const { id, shortcut, action, set } of configuredShortcuts()) {
+    if (!shortcut) continue;
+    const error = await registerShortcutInBackend(id, shortcut, action);
+    if (error) {
+      failures.push(`${bindingLabel(id)}: ${describeShortcutError(error)}`);
+      if (rollBack) set(registered.get(id));
+    }
+  }
+  return failures;
+}
+
+async function registerShortcutInBackend(
+  id: string,
+  shortcut: string,
+  action: ShortcutAction,
+): Promise<ShortcutError | null> {
+  try {
+    await invoke('register_shortcut', { id, shortcutStr: shortcut, action });
+    console.log(`Registered shortcut in backend: ${id} = ${shortcut}`);
+    return null;
   } catch (error) {
     console.error('Failed to register shortcut:', error);
-    updateStatus('error', `Shortcut error: ${error}`);
+    return error as ShortcutError;
+  }
+}
+
+function describeShortcutError(error: ShortcutError): string {
+  switch (error.kind) {
+    case 'conflict':
+      return `${error.shortcut} is already used by "${bindingLabel(error.bindingId)}"`;
+    case 'unavailable':
+      return `${error.shortcut} is taken by another application (${error.message})`;
+    default:
+      return error.message;
+  }
+}
+
+function bindingLabel(bindingId: string): string {
+  const action = ACTIONS.find(({ id }) => id === bindingId);
+  if (action) {
+    return action.label;
   }
+  if (bindingId === dictateBindingId(DEFAULT_PROFILE)) {
+    return 'Recording Shortcut';
+  }
+  const profile = profiles.find((p) => dictateBindingId(p.id) === bindingId);
+  return profile ? profile.name : bindingId;
 }
 
-function updateStatus(status: 'idle' | 'recording' | 'processing' | 'transcribing' | 'success' | 'error', message?: string) {
+function updateStatus(status: StatusKind, message?: string) {
   statusIndicator.className = 'status-indicator ' + status;
   
   const statusMessages: Record<string, string> = {
@@ -81,56 +951,165 @@ function updateStatus(status: 'idle' | 'recording' | 'processing' | 'transcribin
   statusText.textContent = statusMessages[status];
 }
 
+const IS_MAC = navigator.platform.toUpperCase().includes('MAC');
+
+// `KeyboardEvent.code` values whose names differ from what parse_shortcut expects
+const CODE_NAMES: Record<string, string> = {
+  ArrowUp: 'Up',
+  ArrowDown: 'Down',
+  ArrowLeft: 'Left',
+  ArrowRight: 'Right',
+  MediaTrackNext: 'MediaNextTrack',
+  MediaTrackPrevious: 'MediaPrevTrack',
+  AudioVolumeUp: 'VolumeUp',
+  AudioVolumeDown: 'VolumeDown',
+  AudioVolumeMute: 'VolumeMute',
+};
+
+// Resolve with the next key combination pressed (modifier-only presses are ignored).
+// Keys come from `KeyboardEvent.code` so the result does not depend on Shift or the layout.
+function captureShortcut(): Promise<string> {
+  return new Promise((resolve) => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      e.preventDefault();
+
+      // CommandOrControl is Command on macOS and Control elsewhere
+      const parts: string[] = [];
+      if (IS_MAC ? e.metaKey : e.ctrlKey) parts.push('CommandOrControl');
+      if (IS_MAC ? e.ctrlKey : e.metaKey) parts.push(IS_MAC ? 'Control' : 'Super');
+      if (e.altKey) parts.push('Alt');
+      if (e.shiftKey) parts.push('Shift');
+
+      // Add the actual key (ignore modifier-only presses)
+      if (!['Control', 'Shift', 'Alt', 'Meta'].includes(e.key)) {
+        parts.push(CODE_NAMES[e.code] || e.code);
+
+        document.removeEventListener('keydown', handleKeyDown);
+        resolve(parts.join('+'));
+      }
+    };
+
+    document.addEventListener('keydown', handleKeyDown);
+  });
+}
+
 // Event Listeners
-setShortcutBtn.addEventListener('click', () => {
+setShortcutBtn.addEventListener('click', async () => {
   shortcutDisplay.textContent = 'Press keys...';
-  
-  const handleKeyDown = async (e: KeyboardEvent) => {
-    e.preventDefault();
-    
-    const parts: string[] = [];
-    if (e.metaKey || e.ctrlKey) parts.push('CommandOrControl');
-    if (e.altKey) parts.push('Alt');
-    if (e.shiftKey) parts.push('Shift');
-    
-    // Add the actual key (ignore modifier-only presses)
-    if (!['Control', 'Shift', 'Alt', 'Meta'].includes(e.key)) {
-      // Map special keys to their names
-      const keyMap: Record<string, string> = {
-        ' ': 'SPACE',
-        'ArrowUp': 'UP',
-        'ArrowDown': 'DOWN',
-        'ArrowLeft': 'LEFT',
-        'ArrowRight': 'RIGHT',
-        'Enter': 'ENTER',
-        'Escape': 'ESCAPE',
-        'Tab': 'TAB',
-        'Backspace': 'BACKSPACE',
-        'Delete': 'DELETE',
-        'Home': 'HOME',
-        'End': 'END',
-        'PageUp': 'PAGEUP',
-        'PageDown': 'PAGEDOWN',
-      };
-      const keyName = keyMap[e.key] || e.key.toUpperCase();
-      parts.push(keyName);
-      
-      const newShortcut = parts.join('+');
-      shortcutDisplay.textContent = newShortcut;
-      settings.shortcut = newShortcut;
-      
-      document.removeEventListener('keydown', handleKeyDown);
-    }
-  };
-  
-  document.addEventListener('keydown', handleKeyDown);
+
+  const newShortcut = await captureShortcut();
+  shortcutDisplay.textContent = newShortcut;
+  settings.shortcut = newShortcut;
+});
+
+promptProfileSelect.addEventListener('change', loadPrompt);
+promptInput.addEventListener('input', validatePrompt);
+historySearchInput.addEventListener('input', loadHistory);
+rulesTestInput.addEventListener('input', testRules);
+
+addVoiceCommandBtn.addEventListener('click', () => {
+  voiceCommands.commands.push({ phrases: [], action: { type: 'insert', text: '' } });
+  renderVoiceCommands();
+});
+
+addProfileBtn.addEventListener('click', () => {
+  profiles.push({
+    id: `profile-${Date.now().toString(36)}`,
+    name: 'New profile',
+    prompt: 'transcription',
+    model: null,
+    output: 'paste',
+  });
+  renderProfileEditor();
+});
+
+addAppRuleBtn.addEventListener('click', async () => {
+  const app = (await invoke<string | null>('get_last_focused_app')) ?? '';
+  settings.appRules.push({ app, profileId: null, normalize: null });
+  renderAppRules();
+});
+
+addRuleBtn.addEventListener('click', () => {
+  rules.push({
+    kind: 'literal',
+    pattern: '',
+    replacement: '',
+    caseInsensitive: false,
+    wholeWord: false,
+    profiles: [],
+    apps: [],
+    enabled: true,
+  });
+  renderRules();
+});
+
+streamingInput.addEventListener('change', () => {
+  typeProgressivelyInput.disabled = !streamingInput.checked;
 });
 
 saveBtn.addEventListener('click', async () => {
   settings.apiKey = apiKeyInput.value.trim();
   settings.model = modelInput.value.trim();
+  settings.streaming = streamingInput.checked;
+  settings.typeProgressively = typeProgressivelyInput.checked;
+  settings.generationConfig = {
+    ...settings.generationConfig,
+    temperature: parseOptionalNumber(temperatureInput.value),
+    maxOutputTokens: parseOptionalNumber(maxOutputTokensInput.value),
+    thinkingBudget: parseOptionalNumber(thinkingBudgetInput.value),
+  };
+  settings.useSystemInstruction = useSystemInstructionInput.checked;
+  settings.language = languageSelect.value as Language;
+  settings.continuity = {
+    enabled: continuityEnabledInput.checked,
+    // The backend expects positive integers
+    maxTranscripts: Math.max(
+      1,
+      Math.round(parseOptionalNumber(continuityMaxTranscriptsInput.value) ?? DEFAULT_SETTINGS.continuity.maxTranscripts),
+    ),
+    windowSecs: Math.max(
+      1,
+      Math.round(parseOptionalNumber(continuityWindowInput.value) ?? DEFAULT_SETTINGS.continuity.windowSecs),
+    ),
+  };
+  settings.translation = {
+    sourceLanguage: translationSourceInput.value.trim() || DEFAULT_SETTINGS.translation.sourceLanguage,
+    targetLanguage: translationTargetInput.value.trim() || DEFAULT_SETTINGS.translation.targetLanguage,
+    includeOriginal: translationIncludeOriginalInput.checked,
+  };
+  settings.normalize = {
+    alphanumericWidth: normalizeWidthSelect.value as NormalizeSettings['alphanumericWidth'],
+    punctuation: normalizePunctuationSelect.value as NormalizeSettings['punctuation'],
+    spacing: normalizeSpacingSelect.value as NormalizeSettings['spacing'],
+    halfWidthSpace: normalizeHalfWidthSpaceInput.checked,
+  };
+  settings.historyRetention = {
+    maxEntries: parseOptionalNumber(historyMaxEntriesInput.value),
+    maxAgeDays: parseOptionalNumber(historyMaxAgeDaysInput.value),
+  };
+  settings.archive = {
+    enabled: archiveEnabledInput.checked,
+    maxMegabytes: parseOptionalNumber(archiveMaxMegabytesInput.value),
+  };
+  settings.pushToTalk = {
+    ...settings.pushToTalk,
+    enabled: pushToTalkInput.checked,
+    key: pushToTalkKeySelect.value,
+  };
 
   try {
+    // Profiles first: shortcuts and the prompt editor refer to them
+    await invoke('set_profiles', { profiles });
+    settings.profiles = profiles;
+    renderProfiles();
+
+    // Register shortcuts before saving so a rejected one is rolled back
+    // instead of replacing a working binding in settings.json
+    const shortcutFailures = await registerAllShortcuts(true);
+    if (shortcutFailures.length > 0) {
+      renderShortcuts();
+    }
+
     await saveSettings(settings);
 
     // Update API key in Rust backend
@@ -139,11 +1118,42 @@ saveBtn.addEventListener('click', async () => {
     // Update model in Rust backend
     await invoke('set_model', { model: settings.model });
 
-    // Re-register shortcut in Rust backend
-    if (settings.shortcut) {
-      await registerShortcutInBackend(settings.shortcut);
-    }
+    // Update generation config in Rust backend
+    await invoke('set_generation_config', { config: settings.generationConfig });
+    await invoke('set_use_system_instruction', { enabled: settings.useSystemInstruction });
+    await invoke('set_language', { language: settings.language });
+    await invoke('set_continuity_settings', { settings: settings.continuity });
+    await invoke('set_translation_settings', { settings: settings.translation });
+    await invoke('set_normalize_settings', { settings: settings.normalize });
+    settings.appRules = settings.appRules.filter((rule) => rule.app !== '');
+    await invoke('set_app_rules', { rules: settings.appRules });
+    renderAppRules();
+
+    // Save prompt file
+    await invoke('set_prompt', { name: selectedPromptName(), text: promptInput.value });
+    await invoke('set_vocabulary', { terms: parseVocabulary(vocabularyInput.value) });
+    await invoke('set_rules', { rules });
+    voiceCommands.enabled = voiceCommandsEnabledInput.checked;
+    await invoke('set_voice_commands', { settings: voiceCommands });
 
+    // Update streaming options in Rust backend
+    await invoke('set_streaming', {
+      streaming: settings.streaming,
+      typeProgressively: settings.typeProgressively,
+    });
+
+    await invoke('set_push_to_talk', { config: settings.pushToTalk });
+    await invoke('set_history_retention', { retention: settings.historyRetention });
+    await invoke('set_archive_settings', { settings: settings.archive });
+
+    // Leave shortcut errors on screen rather than reporting success
+    if (shortcutFailures.length > 0) {
+      updateStatus(
+        'error',
+        `Saved, but kept previous shortcuts: ${shortcutFailures.join('; ')}`,
+      );
+      return;
+    }
     updateStatus('success', 'Settings saved!');
     setTimeout(() => updateStatus('idle'), 2000);
   } catch (error) {
@@ -1,20 +1,158 @@
+import { ArchiveSettings, ContinuitySettings, HistoryRetention } from './history';
+
+// Mirrors `GenerationSettings` in src-tauri/src/gemini.rs; null keeps the model default
+export interface GenerationConfig {
+  temperature: number | null;
+  maxOutputTokens: number | null;
+  thinkingBudget: number | null;
+  responseMimeType: string | null;
+}
+
+// Mirrors `Profile` in src-tauri/src/profiles.rs
+export interface Profile {
+  id: string;
+  name: string;
+  prompt: string;
+  model: string | null;
+  output: 'paste' | 'clipboard';
+  // Translate with the languages in `translation`
+  translate?: boolean;
+  // Apply spoken commands; unset means only for the default profile
+  voiceCommands?: boolean;
+}
+
+// Mirrors `Language` in src-tauri/src/language.rs
+export type Language = 'auto' | 'ja' | 'en' | 'mixed' | 'zh' | 'ko' | 'es' | 'fr' | 'de';
+
+// Mirrors `TranslationSettings` in src-tauri/src/gemini.rs
+export interface TranslationSettings {
+  sourceLanguage: string;
+  targetLanguage: string;
+  includeOriginal: boolean;
+}
+
+// Mirrors `NormalizeSettings` in src-tauri/src/normalize.rs
+export interface NormalizeSettings {
+  alphanumericWidth: 'keep' | 'half' | 'full';
+  punctuation: 'keep' | 'touten' | 'comma' | 'commaMaru';
+  spacing: 'keep' | 'remove' | 'insert';
+  halfWidthSpace: boolean;
+}
+
+// Mirrors `AppContextRule` in src-tauri/src/focus.rs
+export interface AppContextRule {
+  app: string;
+  profileId: string | null;
+  // null uses the global text style
+  normalize: NormalizeSettings | null;
+}
+
+// Mirrors `PushToTalkConfig` in src-tauri/src/push_to_talk.rs
+export interface PushToTalkConfig {
+  enabled: boolean;
+  key: string;
+  profileId: string;
+  debounceMs: number;
+  minHoldMs: number;
+}
+
 export interface Settings {
   apiKey: string;
   shortcut: string;
   model: string;
+  streaming: boolean;
+  typeProgressively: boolean;
+  generationConfig: GenerationConfig;
+  useSystemInstruction: boolean;
+  // Spoken language, filled into prompts as {{language}}
+  language: Language;
+  // Recent transcripts from the same app sent as context
+  continuity: ContinuitySettings;
+  // Languages for translation profiles
+  translation: TranslationSettings;
+  normalize: NormalizeSettings;
+  // Profile and text style per focused app
+  appRules: AppContextRule[];
+  // Merged over the backend's built-in profiles by ID
+  profiles: Profile[];
+  // Shortcuts for profiles other than the default one, keyed by profile ID
+  profileShortcuts: Record<string, string>;
+  // Shortcuts for non-dictation actions, keyed by binding ID
+  actionShortcuts: Record<string, string>;
+  // Hold a single key (e.g. Right Alt) to dictate; Linux only
+  pushToTalk: PushToTalkConfig;
+  historyRetention: HistoryRetention;
+  // Keep each recording next to its history entry
+  archive: ArchiveSettings;
 }
 
 export const DEFAULT_SETTINGS: Settings = {
   apiKey: '',
   shortcut: 'CommandOrControl+Shift+Space',
   model: 'gemini-3-pro-preview',
+  streaming: false,
+  typeProgressively: false,
+  generationConfig: {
+    temperature: 0,
+    maxOutputTokens: null,
+    thinkingBudget: 0,
+    responseMimeType: 'text/plain',
+  },
+  useSystemInstruction: true,
+  language: 'ja',
+  continuity: {
+    enabled: false,
+    maxTranscripts: 3,
+    windowSecs: 300,
+  },
+  translation: {
+    sourceLanguage: 'Japanese',
+    targetLanguage: 'English',
+    includeOriginal: false,
+  },
+  normalize: {
+    alphanumericWidth: 'keep',
+    punctuation: 'keep',
+    spacing: 'keep',
+    halfWidthSpace: false,
+  },
+  profiles: [],
+  profileShortcuts: {},
+  actionShortcuts: {},
+  appRules: [],
+  pushToTalk: {
+    enabled: false,
+    key: 'RightAlt',
+    profileId: 'default',
+    debounceMs: 50,
+    minHoldMs: 200,
+  },
+  historyRetention: {
+    maxEntries: 1000,
+    maxAgeDays: 30,
+  },
+  archive: {
+    enabled: false,
+    maxMegabytes: 500,
+  },
 };
 
 export async function loadSettings(): Promise<Settings> {
   try {
     const { readTextFile, BaseDirectory } = await import('@tauri-apps/plugin-fs');
     const content = await readTextFile('settings.json', { baseDir: BaseDirectory.AppData });
-    return { ...DEFAULT_SETTINGS, ...JSON.parse(content) };
+    const saved = JSON.parse(content);
+    return {
+      ...DEFAULT_SETTINGS,
+      ...saved,
+      generationConfig: { ...DEFAULT_SETTINGS.generationConfig, ...saved.generationConfig },
+      continuity: { ...DEFAULT_SETTINGS.continuity, ...saved.continuity },
+      translation: { ...DEFAULT_SETTINGS.translation, ...saved.translation },
+      normalize: { ...DEFAULT_SETTINGS.normalize, ...saved.normalize },
+      pushToTalk: { ...DEFAULT_SETTINGS.pushToTalk, ...saved.pushToTalk },
+      historyRetention: { ...DEFAULT_SETTINGS.historyRetention, ...saved.historyRetention },
+      archive: { ...DEFAULT_SETTINGS.archive, ...saved.archive },
+    };
   } catch {
     return { ...DEFAULT_SETTINGS };
   }
@@ -0,0 +1,29 @@
+// Mirrors `Status` in src-tauri/src/status.rs (payload of `status-changed`).
+export type Status =
+  | { kind: 'idle' }
+  | { kind: 'recording'; jobId: number }
+  | { kind: 'processing'; jobId: number; elapsedMs: number; audioDurationMs: number }
+  | {
+      kind: 'transcribing';
+      jobId: number;
+      elapsedMs: number;
+      audioDurationMs: number;
+      byteSize: number;
+    }
+  | {
+      kind: 'success';
+      jobId: number;
+      elapsedMs: number;
+      audioDurationMs: number;
+      byteSize: number;
+    }
+  | { kind: 'error'; jobId: number | null; elapsedMs: number | null; message: string };
+
+export type StatusKind = Status['kind'];
+
+// Payload of `transcript-partial` (streamed responses only).
+export interface PartialTranscript {
+  jobId: number;
+  delta: string;
+  text: string;
+}